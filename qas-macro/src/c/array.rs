@@ -0,0 +1,163 @@
+use crate::StringExt;
+use super::{Expr, TypeID, BuiltinType, FullType, Function, Assign, Unop, Binop, Record, Global};
+
+pub struct Array;
+
+impl Array {
+    /// Array used as value turns into pointer to its first element
+    pub fn decay(x: Expr) -> Expr {
+        if x.ty.dims.is_empty() {
+            return x
+        } else if x.ty.dims.len() > 1 {
+            panic!("pointers to arrays are not supported")
+        }
+
+        let ty = x.ty.element().with_ptr(vec![true]);
+        let method = if x.ty.mutable { "as_mut_ptr" } else { "as_ptr" };
+        match &x.place {
+            Some(place) => {
+                if Global::is_static_mut(place) {
+                    return Expr::new(format!("({} as {})", Global::address(place, x.ty.mutable), FullType::real(&ty)), &ty)
+                } else if x.ty.mutable {
+                    Function::check_and_make_mutable_on_require(place)
+                }
+                Expr::new(Assign::guard(&x, format!("{}.{}()", place, method)), &ty)
+            },
+            None => Expr::new(format!("{}.{}()", Binop::operand(x.name), method), &ty)
+        }
+    }
+
+    /// `x[i]`, where `x` is either array or pointer
+    pub fn index(x: Expr, i: Expr) -> Expr {
+        if !BuiltinType::is_integer(&i.ty) {
+            panic!("array subscript is not an integer")
+        }
+
+        if x.ty.dims.is_empty() {
+            if x.ty.ptr.is_empty() {
+                panic!("subscripted value is neither array nor pointer")
+            }
+
+            // `p[i]` is `*(p + i)`, where `unsafe` of dereference covers the offset too
+            let ty = x.ty.clone();
+            return Unop::union(Expr::new(Binop::offset(&x, "+", i), &ty), "*")
+        }
+
+        let is_literal = i.name.chars().all(|x| x.is_ascii_digit());
+
+        let i = if is_literal {
+            i.name
+        } else {
+            format!("{} as usize", Binop::operand(i.name))
+        };
+        let ty = x.ty.element();
+        match &x.place {
+            Some(place) => {
+                let place = format!("{}[{}]", if place.starts_with('*') {
+                    place.parentify()
+                } else {
+                    place.clone()
+                }, i);
+                let mut y = Expr {
+                    union: x.union,
+                    ..Expr::lvalue(String::new(), place, &ty)
+                };
+                y.name = Assign::read(&y);
+                y
+            },
+            None => Expr::new(format!("{}[{}]", Binop::operand(x.name), i), &ty)
+        }
+    }
+}
+
+/// Initializer of a variable or of a part of it
+pub enum Init {
+    Expr(Expr),
+    /// `{ ... }`
+    List(Vec <Init>)
+}
+
+impl Init {
+    /// Size of array, whose size is not written, but taken from initializer
+    pub fn array_len(&self) -> usize {
+        match self {
+            Self::List(x) => x.len(),
+            Self::Expr(x) if x.name.starts_with('"') => Self::string_len(&x.name),
+            Self::Expr(_) => panic!("array must be initialized with a brace-enclosed initializer list")
+        }
+    }
+
+    /// Number of characters in string literal, counting escape sequences once
+    fn string_len(x: &str) -> usize {
+        let mut chars = x[1..x.len() - 1].chars();
+        let mut len = 0;
+        while let Some(c) = chars.next() {
+            if c == '\\' && chars.next() == Some('x') {
+                chars.nth(1);
+            }
+            len += 1
+        }
+        len
+    }
+
+    /// Rust value of type `ty` made of the initializer
+    pub fn lower(self, ty: &TypeID) -> String {
+        match self {
+            Self::Expr(x) if !ty.dims.is_empty() => {
+                if !x.name.starts_with('"') || FullType::real(&ty.element()) != "u8" {
+                    panic!("array must be initialized with a brace-enclosed initializer list")
+                }
+
+                let len = Self::string_len(&x.name);
+                if len == ty.dims[0] {
+                    format!("*b{}", x.name)
+                } else if len == ty.dims[0] + 1 {
+                    // there is no room for the terminating null
+                    format!("*b{}\"", &x.name[..x.name.len() - 2])
+                } else if len < ty.dims[0] {
+                    format!("::qas::builtin::pad(*b{})", x.name)
+                } else {
+                    panic!("initializer-string for array is too long")
+                }
+            },
+            Self::Expr(x) => BuiltinType::convert(&x.ty, ty, &x.name).deparentify(),
+            Self::List(x) if x.is_empty() => String::from("::qas::builtin::zeroed()"),
+            Self::List(x) if !ty.dims.is_empty() => {
+                if x.len() > ty.dims[0] {
+                    panic!("excess elements in array initializer")
+                }
+
+                let is_full = x.len() == ty.dims[0];
+                let element = ty.element();
+                let items = x.into_iter().map(|x| x.lower(&element)).collect::<Vec <_>>().join(", ");
+                if is_full {
+                    format!("[{}]", items)
+                } else {
+                    format!("::qas::builtin::pad([{}])", items)
+                }
+            },
+            Self::List(x) if Record::is_record(ty) => {
+                let fields = &FullType::types()[ty.idx].fields;
+                if x.len() > fields.len() || (Record::is_union(ty) && x.len() > 1) {
+                    panic!("excess elements in `{}` initializer", FullType::raw(ty))
+                }
+
+                let is_full = x.len() == fields.len();
+                let items = x.into_iter().zip(fields.iter().enumerate()).map(|(x, (i, field))| {
+                    format!("{}: {}", Record::field_name(field, i), x.lower(&field.ty))
+                }).collect::<Vec <_>>().join(", ");
+                format!("{} {{ {}{} }}", FullType::real(ty), items, if is_full || Record::is_union(ty) {
+                    ""
+                } else {
+                    ", ..::qas::builtin::zeroed()"
+                })
+            },
+            Self::List(mut x) => {
+                if x.len() > 1 {
+                    panic!("excess elements in scalar initializer")
+                }
+                x.pop().unwrap().lower(ty)
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::StringExt;
-use super::super::{FullType, TypeID};
+use super::super::{FullType, TypeID, Constant};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 #[repr(u8)]
@@ -61,11 +61,18 @@ impl BuiltinType {
 
         FullType::add_builtin("f32", 4);
         FullType::add_builtin("f64", 8);
+
+        // `Count` stands for "same type" in operators, so user types go after it
+        FullType::add_builtin("_", 0);
     }
 
     #[inline]
     fn id2self(id: &TypeID) -> Self {
-        unsafe { *(&id.idx as *const usize as *const Self) }
+        if id.idx < Self::Count as usize && id.dims.is_empty() {
+            unsafe { *(&(id.idx as u8) as *const u8 as *const Self) }
+        } else {
+            Self::Count
+        }
     }
 
     #[inline]
@@ -75,7 +82,7 @@ impl BuiltinType {
 
     #[inline]
     pub fn is_builtin(ty: &TypeID) -> bool {
-        Self::Count.as_id().idx > ty.idx || !ty.ptr.is_empty()
+        (Self::Count.as_id().idx > ty.idx || !ty.ptr.is_empty()) && ty.dims.is_empty()
     }
 
     #[inline]
@@ -106,6 +113,69 @@ impl BuiltinType {
         Self::is_integer(ty) || Self::is_floating_point(ty) || Self::is_pointer(ty)
     }
 
+    /// Integer, floating or `_Bool`, i.e. type, which takes part in usual arithmetic conversions
+    #[inline]
+    pub fn is_number(ty: &TypeID) -> bool {
+        Self::is_integer(ty) || Self::is_floating_point(ty) || Self::id2self(ty) == Self::Bool
+    }
+
+    /// Integer conversion rank; `long long` is the same type as `long`
+    fn rank(ty: &TypeID) -> u8 {
+        match Self::id2self(ty) {
+            Self::Bool => 0,
+            Self::SignedChar | Self::UnsignedChar => 1,
+            Self::SignedShort | Self::UnsignedShort => 2,
+            Self::SignedInt | Self::UnsignedInt => 3,
+            _ => 4
+        }
+    }
+
+    /// Integer promotion: types of lower rank than `int` become `int`, which
+    /// represents all their values
+    pub fn promote(ty: &TypeID) -> TypeID {
+        if Self::is_number(ty) && !Self::is_floating_point(ty) && Self::rank(ty) < Self::rank(&Self::SignedInt.as_id()) {
+            Self::SignedInt.as_id()
+        } else {
+            ty.clone()
+        }
+    }
+
+    /// Common type of two numbers by the usual arithmetic conversions
+    pub fn usual(a: &TypeID, b: &TypeID) -> TypeID {
+        for x in [Self::Double, Self::Float] {
+            if Self::id2self(a) == x || Self::id2self(b) == x {
+                return x.as_id()
+            }
+        }
+
+        let (a, b) = (Self::promote(a), Self::promote(b));
+        if a == b {
+            a
+        } else if Self::is_signed(&a) == Self::is_signed(&b) {
+            if Self::rank(&a) > Self::rank(&b) { a } else { b }
+        } else {
+            let (signed, unsigned) = if Self::is_signed(&a) { (a, b) } else { (b, a) };
+            if Self::rank(&unsigned) >= Self::rank(&signed) {
+                unsigned
+            } else if Self::range(&signed).1 >= Self::range(&unsigned).1 {
+                signed
+            } else {
+                Self::unsigned(&signed).as_id()
+            }
+        }
+    }
+
+    /// Unsigned type corresponding to signed integer type
+    fn unsigned(ty: &TypeID) -> Self {
+        match Self::id2self(ty) {
+            Self::SignedChar => Self::UnsignedChar,
+            Self::SignedShort => Self::UnsignedShort,
+            Self::SignedInt => Self::UnsignedInt,
+            Self::SignedLong => Self::UnsignedLong,
+            _ => unreachable!()
+        }
+    }
+
     pub fn dominant(a: &TypeID, b: &TypeID) -> Dominant {
         if a == b { Dominant::Similar }
         else {
@@ -118,6 +188,16 @@ impl BuiltinType {
         }
     }
 
+    /// The smallest and the biggest values of integer type
+    pub fn range(ty: &TypeID) -> (i128, i128) {
+        let bits = FullType::size(ty) as u32 * 8;
+        if Self::is_signed(ty) {
+            (-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+        } else {
+            (0, (1 << bits) - 1)
+        }
+    }
+
     #[cfg(target_pointer_width = "64")]
     pub const fn usized() -> Self {
         Self::UnsignedLong
@@ -139,11 +219,31 @@ impl BuiltinType {
             if b.ptr.is_empty() {
                 panic!("cannot cast string to non-ptr type")
             }
+            // C strings are bytes, unless they have wide characters
+            if data.is_ascii() {
+                data.insert(0, 'b')
+            }
             data.push_str(".as_ptr()");
         }
 
         if a == *b { return data }
 
+        if *b == BuiltinType::Bool.as_id() && Self::is_arithmetic(&a) {
+            // C truthiness: any scalar is true when it is nonzero
+            let data = if data.chars().any(|x| !x.is_alphanumeric() && x != '_') {
+                format!("({})", data)
+            } else {
+                data
+            };
+            return if Self::is_pointer(&a) {
+                format!("(!{}.is_null())", data)
+            } else if Self::is_floating_point(&a) {
+                format!("({} != 0.)", data)
+            } else {
+                format!("({} != 0)", data)
+            }
+        }
+
         let mut convert_to_ulong = |a: &mut TypeID| {
             let ulong = BuiltinType::UnsignedLong.as_id();
             data = Self::convert(a, &ulong, &data).deparentify();
@@ -177,25 +277,33 @@ impl BuiltinType {
         }
 
         if Self::is_builtin(&a) && Self::is_builtin(&b) {
-            if data.chars().next().unwrap().is_numeric() && BuiltinType::is_arithmetic(&b) {
-                // has number dot or not
-                match data.chars().enumerate().find(|(_, x)| *x == '.') {
-                    // float
-                    Some((dot, _)) => if BuiltinType::is_floating_point(&b) {
-                        // float -> float, so do nothing
-                        data
-                    } else {
-                        // float -> int, so erase dot and everything after
-                        data[..dot].to_string()
-                    },
-                    // int
-                    None => if BuiltinType::is_floating_point(&b) {
-                        // int -> float, so add dot to the end
-                        data + "."
+            if data == "0" && Self::is_pointer(&b) {
+                // null pointer constant
+                format!("::core::ptr::{}()", if b.ptr[b.ptr.len() - 1] { "null_mut" } else { "null" })
+            } else if let (Some(value), false) = (Constant::int_literal(&data), Self::is_pointer(&b)) {
+                if BuiltinType::is_floating_point(&b) {
+                    // int -> float, so add dot to the end
+                    format!("{}.", value)
+                } else {
+                    // int -> int, which is truncated, if it does not fit
+                    let wrapped = Constant::wrap(value, &b);
+                    Constant::typed(if wrapped == value {
+                        Constant::unsuffixed(&data).to_string()
                     } else {
-                        // int -> int, so do nothing
-                        data
+                        wrapped.to_string()
+                    }, wrapped, &b)
+                }
+            } else if let (Some(value), false) = (Constant::float_literal(&data), Self::is_pointer(&b)) {
+                if BuiltinType::is_floating_point(&b) {
+                    // float -> float, so do nothing
+                    data
+                } else {
+                    // float -> int, so drop fractional part
+                    let value = value.trunc() as i128;
+                    if value < BuiltinType::range(&b).0 || value > BuiltinType::range(&b).1 {
+                        panic!("`{}` does not fit into `{}`", data, FullType::raw(&b))
                     }
+                    Constant::typed(value.to_string(), value, &b)
                 }
             } else {
                 format!("({} as {})", if data.chars().find(|x| !x.is_alphanumeric()).is_some() {
@@ -11,12 +11,17 @@ impl Comment {
             for comment in comments {
                 if code.chars().skip(i).collect::<String>().starts_with(comment.begin) {
                     code.drain(i..i + comment.begin.len());
+                    // lines of comment are kept, so that lines of code stay the same
+                    let mut lines = 0;
                     while !code[i..].starts_with(comment.end) {
-                        code.remove(i);
+                        if code.remove(i) == '\n' {
+                            lines += 1
+                        }
                     }
                     if !comment.save_end {
                         code.drain(i..i + comment.end.len());
                     }
+                    code.insert_str(i, &"\n".repeat(lines));
                 }
             }
             i += 1
@@ -0,0 +1,238 @@
+use super::{BuiltinType, TypeID, FullType, Function, Record, Tab, AliasType};
+
+/// Helpers for integer constant expressions, which are evaluated while translating
+pub struct Constant;
+
+impl Constant {
+    /// Value of an integer literal as returned by `num`
+    pub fn parse(num: &str) -> i128 {
+        match Self::int_literal(num) {
+            Some(x) => x,
+            None => panic!("`{}` is not an integer constant", num)
+        }
+    }
+
+    /// Value of integer literal, which may be negated, as written in Rust
+    pub fn int_literal(x: &str) -> Option <i128> {
+        let (sign, digits) = match x.strip_prefix('-') {
+            Some(x) => (-1, x),
+            None => (1, x)
+        };
+        let digits = Self::unsuffixed(digits);
+        let (radix, digits) = if let Some(x) = digits.strip_prefix("0b") {
+            (2, x)
+        } else if let Some(x) = digits.strip_prefix("0o") {
+            (8, x)
+        } else if let Some(x) = digits.strip_prefix("0x") {
+            (16, x)
+        } else {
+            (10, digits)
+        };
+
+        if digits.is_empty() || !digits.chars().all(|x| x.is_digit(radix)) {
+            return None
+        }
+        i128::from_str_radix(digits, radix).ok().map(|x| sign * x)
+    }
+
+    /// Value of floating literal, which may be negated, as written in Rust
+    pub fn float_literal(x: &str) -> Option <f64> {
+        let digits = x.strip_prefix('-').unwrap_or(x);
+        if !digits.starts_with(|x: char| x.is_ascii_digit()) || Self::int_literal(x).is_some() {
+            return None
+        }
+        x.parse().ok()
+    }
+
+    /// Hexadecimal floating literal `0x{mantissa}p{exp}` written in decimal,
+    /// as Rust does not have such literals
+    pub fn hex_float(mantissa: &str, exp: &str) -> String {
+        let mut value = 0.;
+        let mut exp = exp.parse::<i32>().expect("exponent is too large");
+        for x in mantissa.chars() {
+            match x.to_digit(16) {
+                Some(digit) => value = value * 16. + digit as f64,
+                None => exp -= 4 * (mantissa.len() - mantissa.find('.').unwrap() - 1) as i32
+            }
+        }
+        format!("{:?}", value * 2f64.powi(exp))
+    }
+
+    /// Integer literal `x` of type `ty`, whose value is `value`; it has Rust
+    /// suffix, unless it fits into `i32`, that is the type of unsuffixed literal
+    pub fn typed(x: String, value: i128, ty: &TypeID) -> String {
+        if i32::MIN as i128 <= value && value <= i32::MAX as i128 {
+            x
+        } else {
+            x + &FullType::real(ty)
+        }
+    }
+
+    /// Integer literal without the suffix added by `typed`
+    pub fn unsuffixed(x: &str) -> &str {
+        ["i64", "u64", "i32", "u32"].iter().find_map(|suffix| x.strip_suffix(suffix)).unwrap_or(x)
+    }
+
+    /// `value` converted to integer type `ty` modulo its size
+    pub fn wrap(value: i128, ty: &TypeID) -> i128 {
+        let (min, max) = BuiltinType::range(ty);
+        let modulo = max - min + 1;
+        (value - min).rem_euclid(modulo) + min
+    }
+
+    /// Panics if `value` cannot be represented by `ty`
+    pub fn check(value: i128, ty: &TypeID) {
+        let (min, max) = BuiltinType::range(ty);
+        if value < min || value > max {
+            panic!("constant `{}` does not fit into `{}`", value, FullType::real(ty))
+        }
+    }
+
+    /// Code of character written after backslash, except of octal and hex escapes
+    pub fn escape(x: &str) -> u32 {
+        match x {
+            "n" => 0x0A,
+            "t" => 0x09,
+            "r" => 0x0D,
+            "a" => 0x07,
+            "b" => 0x08,
+            "f" => 0x0C,
+            "v" => 0x0B,
+            "\\" | "'" | "\"" | "?" => x.chars().next().unwrap() as u32,
+            _ => panic!("unknown escape sequence `\\{}`", x)
+        }
+    }
+
+    /// Character of C string as it is written in Rust byte string, which
+    /// has no octal escapes; wide strings keep other characters as they are
+    pub fn string_char(x: u32, ext: bool) -> String {
+        if ext && x > 0xFF {
+            panic!("escape sequence `{:#x}` is out of range", x)
+        }
+
+        match x {
+            0 => String::from("\\0"),
+            0x09 => String::from("\\t"),
+            0x0A => String::from("\\n"),
+            0x0D => String::from("\\r"),
+            0x22 => String::from("\\\""),
+            0x5C => String::from("\\\\"),
+            0x20..=0x7E => char::from(x as u8).to_string(),
+            0..=0x7F => format!("\\x{:02x}", x),
+            0x80..=0xFF if ext => format!("\\x{:02x}", x),
+            _ => match char::from_u32(x) {
+                Some(x) => x.to_string(),
+                None => panic!("escape sequence `{:#x}` is not a character", x)
+            }
+        }
+    }
+
+    /// Value of character constant made of `chars`
+    pub fn character(chars: Vec <u32>, ext: bool) -> u32 {
+        if chars.len() > 1 {
+            panic!("multi-character constants are not supported")
+        } else if ext && chars[0] > 0xFF {
+            panic!("escape sequence `{:#x}` is out of range", chars[0])
+        }
+        chars[0]
+    }
+
+    pub fn div(x: i128, y: i128, op: &str) -> i128 {
+        if y == 0 {
+            panic!("division by zero in constant expression")
+        }
+        if op == "/" { x / y } else { x % y }
+    }
+}
+
+/// Constant declared by `enum`
+pub struct Enumerator {
+    pub name: String,
+    pub value: i128,
+    /// Position in code, so that parsing the same enumerator again does not declare it twice
+    pos: usize,
+    is_in_function: bool
+}
+
+impl Enumerator {
+    #[inline]
+    pub fn enumerators() -> &'static mut Vec <Enumerator> {
+        static mut ENUMERATORS: Vec <Enumerator> = Vec::new();
+        unsafe { &mut ENUMERATORS }
+    }
+
+    /// Value of the enumerator without initializer
+    fn next() -> &'static mut i128 {
+        static mut NEXT: i128 = 0;
+        unsafe { &mut NEXT }
+    }
+
+    /// Positions of `enum` definitions met so far
+    fn defined() -> &'static mut Vec <usize> {
+        static mut DEFINED: Vec <usize> = Vec::new();
+        unsafe { &mut DEFINED }
+    }
+
+    /// Forgets the definitions of the previous file
+    pub fn reset() {
+        Self::defined().clear();
+        Self::enumerators().clear()
+    }
+
+    /// Declares `enum name` found at `pos`
+    pub fn tag(pos: usize, name: &str) {
+        if Self::defined().contains(&pos) {
+            return
+        }
+        Self::defined().push(pos);
+
+        let tag = format!("enum {}", name);
+        if AliasType::types().iter().any(|x| x.name == tag && x.is_in_function == Function::is_in_body()) {
+            panic!("redefinition of `{}`", tag)
+        }
+        AliasType::add(tag, BuiltinType::SignedInt.as_id())
+    }
+
+    /// Starts the list of enumerators
+    pub fn begin() {
+        *Self::next() = 0
+    }
+
+    /// Declares the enumerator found at `pos`, emitting `const` item for it
+    pub fn add(pos: usize, name: &str, value: Option <i128>) {
+        if let Some(x) = Self::enumerators().iter().find(|x| x.pos == pos) {
+            *Self::next() = x.value + 1;
+            return
+        }
+
+        let is_in_function = Function::is_in_body();
+        if Self::enumerators().iter().any(|x| x.name == name && x.is_in_function == is_in_function) {
+            panic!("redefinition of enumerator `{}`", name)
+        }
+
+        let value = value.unwrap_or(*Self::next());
+        Constant::check(value, &BuiltinType::SignedInt.as_id());
+        *Self::next() = value + 1;
+
+        Self::enumerators().push(Self {
+            name: name.to_string(),
+            value,
+            pos,
+            is_in_function
+        });
+        Record::pending().push(format!("{}const {}: i32 = {};", if is_in_function {
+            // C does not mind unused enumerators
+            format!("#[allow(dead_code)]\n{}", Tab::tabs())
+        } else {
+            String::from("pub ")
+        }, name, value))
+    }
+
+    pub fn find(name: &str) -> Option <&'static Enumerator> {
+        Self::enumerators().iter().rev().find(|x| x.name == name)
+    }
+
+    pub fn clear() {
+        Self::enumerators().retain(|x| !x.is_in_function)
+    }
+}
@@ -0,0 +1,301 @@
+use crate::StringExt;
+use super::{Function, Jump, Expr, BuiltinType, Constant, Tab, Let, FullType, TypeID};
+
+pub struct Loop;
+
+impl Loop {
+    /// Resolves `break` and `continue` of the loop labeled `'l{label}`
+    pub fn jumps(body: String, label: usize, r#continue: String) -> String {
+        body.replace(Jump::BREAK, &format!("break 'l{};", label)).replace(Jump::CONTINUE, &r#continue)
+    }
+
+    /// Label for the loop, if anything refers to it
+    pub fn label(body: &str, label: usize) -> String {
+        let label = format!("'l{}", label);
+        if body.contains(&format!("{};", label)) {
+            format!("{}: ", label)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Wraps body into a block labeled `'c{label}`, if `continue` is used,
+    /// so that it may skip to the end of the iteration
+    pub fn continuable(body: String, label: usize) -> String {
+        let label = format!("'c{}", label);
+        if body.contains(&format!("{};", label)) {
+            format!("{}: {}", label, body)
+        } else {
+            body
+        }
+    }
+
+    /// Whether the bound of a range loop cannot change while iterating
+    pub fn is_invariant(bound: &str) -> bool {
+        if bound.chars().all(|x| x.is_alphanumeric() || x == '_') {
+            match Function::get().find_let(bound) {
+                Some(x) => !x.mutable,
+                None => bound.chars().next().map_or(false, |x| x.is_numeric())
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Whether `counter op bound` compares as the counter does in a range loop:
+    /// the bound has to keep its value in the type of the counter, and `<=`
+    /// must not stop at the maximum, where the counter of C wraps around
+    pub fn is_range_bound(counter: &TypeID, op: &str, bound: &Expr) -> bool {
+        if !BuiltinType::is_integer(&bound.ty) || BuiltinType::is_pointer(&bound.ty) {
+            return false
+        }
+        match Constant::int_literal(&bound.name) {
+            Some(value) => {
+                let (min, max) = BuiltinType::range(counter);
+                min <= value && (value < max || op == "<" && value == max)
+            },
+            None => BuiltinType::usual(counter, &bound.ty) == *counter && (op == "<" || BuiltinType::is_signed(counter))
+        }
+    }
+}
+
+pub struct Switch;
+
+impl Switch {
+    /// Translates `switch` labeled `'s{label}`; each group is labels with
+    /// the statements following them, `None` standing for `default`
+    pub fn lower(value: Expr, groups: Vec <(Vec <Option <i128>>, Vec <String>)>, label: usize) -> String {
+        if !BuiltinType::is_integer(&value.ty) {
+            panic!("switch quantity is not an integer")
+        }
+
+        let mut seen = Vec::new();
+        let mut has_default = false;
+        for x in groups.iter().flat_map(|x| x.0.iter()) {
+            match x {
+                Some(x) => {
+                    Constant::check(*x, &value.ty);
+                    if seen.contains(x) {
+                        panic!("duplicate case value `{}`", x)
+                    }
+                    seen.push(*x)
+                },
+                None => if has_default {
+                    panic!("multiple default labels in one switch")
+                } else {
+                    has_default = true
+                }
+            }
+        }
+
+        let tabs = Tab::tabs();
+        let brk = format!("break 's{};", label);
+        let value = value.name.deparentify();
+
+        // every group but the last one has to leave the switch by itself
+        let falls = groups.iter().rev().skip(1).any(|x| !Self::ends(&x.1));
+
+        if !falls {
+            let mut arms = String::new();
+            let mut default = None;
+            for (labels, mut stmts) in groups {
+                if stmts.last().map_or(false, |x| x == Jump::BREAK) {
+                    stmts.pop();
+                }
+                let body = Self::body(&stmts, &brk, 2);
+                if labels.contains(&None) {
+                    default = Some(body)
+                } else {
+                    arms.push_str(&format!("\n{}\t{} => {},", tabs, Self::pattern(&labels), body))
+                }
+            }
+            arms.push_str(&format!("\n{}\t_ => {}", tabs, default.unwrap_or_else(|| String::from("{}"))));
+
+            format!("match {} {{{}\n{}}}", value, arms, tabs)
+        } else {
+            // every group follows the block, which `match` leaves to jump to it
+            let mut arms = String::new();
+            for (idx, (labels, _)) in groups.iter().enumerate() {
+                if !labels.contains(&None) {
+                    arms.push_str(&format!("\n{}\t{} => break 's{}g{},", tabs, Self::pattern(labels), label, idx))
+                }
+            }
+            arms.push_str(&format!("\n{}\t_ => break 's{}{},", tabs, label, match groups.iter().position(|x| x.0.contains(&None)) {
+                Some(idx) => format!("g{}", idx),
+                None => String::new()
+            }));
+
+            let mut code = format!("match {} {{{}\n{}}}", value, arms, tabs);
+            for (idx, (_, stmts)) in groups.iter().enumerate() {
+                code = format!("'s{}g{}: {{\n{}\t{}\n{}}}", label, idx, tabs, Tab::indent(&code), tabs);
+                if !stmts.is_empty() {
+                    code = format!("{}\n{}{}", code, tabs, stmts.join(&Tab::tabs_nl()).replace(Jump::BREAK, &brk))
+                }
+            }
+            code
+        }
+    }
+
+    /// Label for the whole switch, if anything refers to it
+    pub fn label(code: &str, label: usize) -> String {
+        if code.contains(&format!("'s{};", label)) || code.contains(&format!("'s{},", label)) {
+            format!("'s{}: ", label)
+        } else {
+            String::new()
+        }
+    }
+
+    fn pattern(labels: &[Option <i128>]) -> String {
+        labels.iter().map(|x| x.unwrap().to_string()).collect::<Vec <_>>().join(" | ")
+    }
+
+    /// Whether the statements never fall through to the next group
+    fn ends(stmts: &[String]) -> bool {
+        match stmts.last() {
+            Some(x) => x == Jump::BREAK || x == Jump::CONTINUE || x.starts_with("return"),
+            None => false
+        }
+    }
+
+    /// Block of statements, indented by `shift` levels
+    fn body(stmts: &[String], brk: &str, shift: usize) -> String {
+        if stmts.is_empty() {
+            return String::from("{}")
+        }
+        let mut body = stmts.join(&Tab::tabs_nl()).replace(Jump::BREAK, brk);
+        for _ in 0..shift {
+            body = Tab::indent(&body)
+        }
+        let tabs = Tab::tabs() + &"\t".repeat(shift);
+        format!("{{\n{}{}\n{}}}", tabs, body, &tabs[1..])
+    }
+}
+
+pub struct Goto;
+
+impl Goto {
+    /// Resolves labels placed right inside the block made of `stmts`,
+    /// together with every `goto` to them found in the block.
+    ///
+    /// If all of them jump forward, every label closes a labeled block, which
+    /// `goto` breaks out of. Otherwise, the block becomes a loop, which
+    /// chooses the place to start from on every iteration
+    pub fn lower(stmts: Vec <String>) -> Vec <String> {
+        // every segment is preceded by a label, except for the first one
+        let mut labels = Vec::new();
+        let mut segments = vec![Vec::new()];
+        for mut stmt in stmts {
+            while stmt.starts_with('\u{3}') && !stmt.starts_with("\u{3}goto ") {
+                let end = stmt[1..].find('\u{3}').unwrap() + 2;
+                let name = stmt[1..end - 2].to_string();
+                if labels.contains(&name) {
+                    panic!("duplicate label `{}`", name)
+                }
+                labels.push(name);
+                segments.push(Vec::new());
+                stmt = stmt[end..].to_string()
+            }
+            segments.last_mut().unwrap().push(stmt)
+        }
+
+        if labels.is_empty() {
+            return segments.pop().unwrap()
+        }
+
+        // C does not mind code right after `goto`
+        let allow = String::from("allow(unreachable_code)");
+        if !Function::get().attrs.contains(&allow) {
+            Function::get().attrs.push(allow)
+        }
+
+        let label = Jump::label();
+        let tabs = Tab::tabs();
+        let state = format!("__goto{}", label);
+        let is_forward = labels.iter().enumerate().all(|(i, name)| {
+            segments[i + 1..].iter().flatten().all(|x| !x.contains(&Jump::goto(name)))
+        });
+
+        // segment `j` lies inside of the blocks of all the following labels
+        for (j, segment) in segments.iter_mut().enumerate() {
+            for stmt in segment.iter_mut() {
+                for (i, name) in labels.iter().enumerate() {
+                    *stmt = stmt.replace(&Jump::goto(name), &if j <= i {
+                        format!("break 'g{}_{};", label, i + 1)
+                    } else {
+                        format!("{{ {} = {}; continue 'd{}; }}", state, i + 1, label)
+                    })
+                }
+            }
+        }
+
+        // segments end up in different blocks, so their variables are declared beforehand
+        let mut result = Vec::new();
+        let last = segments.len() - is_forward as usize;
+        for stmt in segments[..last].iter_mut().flatten() {
+            Self::hoist(stmt, &mut result)
+        }
+
+        let mut code = if is_forward {
+            String::new()
+        } else {
+            let mut arms = String::new();
+            for i in 1..segments.len() {
+                arms.push_str(&format!("\n{}\t{} => break 'g{}_{},", tabs, i, label, i))
+            }
+            format!("match {} {{{}\n{}\t_ => break 'g{}_0,\n{}}}", state, arms, tabs, label, tabs)
+        };
+
+        // the value of the function, if the block is its whole body
+        let is_value = segments.last().unwrap().last().map_or(false, |x| !x.ends_with(';'));
+        let value = if !is_forward && is_value && *Tab::number_tabs() == 0 && Function::get().ret != BuiltinType::Void.as_id() {
+            segments.last_mut().unwrap().pop()
+        } else {
+            None
+        };
+        // the loop, which ends with `return`, is never left by `break`
+        let returns = segments.last().unwrap().last().map_or(false, |x| x.starts_with("return"));
+
+        for (i, segment) in segments.iter().enumerate() {
+            let name = format!("'g{}_{}", label, i);
+            if code.contains(&format!("{};", name)) || code.contains(&format!("{},", name)) {
+                code = format!("{}: {{\n{}\t{}\n{}}}", name, tabs, Tab::indent(&code), tabs)
+            }
+            if !segment.is_empty() {
+                if !code.is_empty() {
+                    code.push_str(&Tab::tabs_nl())
+                }
+                code.push_str(&segment.join(&Tab::tabs_nl()))
+            }
+        }
+
+        if !is_forward {
+            result.push(format!("let mut {}: u32 = 0;", state));
+            let end = if returns {
+                String::new()
+            } else {
+                format!("\n{}\tbreak 'd{}{};", tabs, label, value.map(|x| format!(" {}", x)).unwrap_or_default())
+            };
+            code = format!("'d{}: loop {{\n{}\t{}{}\n{}}}", label, tabs, Tab::indent(&code), end, tabs)
+        }
+
+        result.push(code);
+        result
+    }
+
+    /// Moves declarations out of `stmt` into `lets`, leaving assignments instead
+    fn hoist(stmt: &mut String, lets: &mut Vec <String>) {
+        if !stmt.starts_with("let ") {
+            return
+        }
+
+        let idxs = stmt.split("let \u{1}").skip(1).map(|x| x[..x.find('\u{1}').unwrap()].parse::<usize>().unwrap()).collect::<Vec <_>>();
+        for idx in idxs {
+            let x = &mut Function::get().lets[idx];
+            x.mutable = true;
+
+            let decl = format!("let {}{}: {} = ", Let::marker(idx), x.real, FullType::real(&x.ty));
+            lets.push(format!("{}::qas::builtin::zeroed();", decl));
+            *stmt = stmt.replacen(&decl, &format!("{} = ", x.real), 1)
+        }
+    }
+}
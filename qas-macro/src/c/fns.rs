@@ -1,9 +1,22 @@
-use super::{TypeID, BuiltinFunction};
+use crate::StringExt;
+use super::{clang, TypeID, BuiltinFunction, BuiltinType, FullType, Init, Global};
 
 bitflags::bitflags! {
     pub struct FnFlags: u8 {
-        const SAFE   = 0b01;
-        const PUBLIC = 0b10;
+        const SAFE      = 0b000001;
+        const PUBLIC    = 0b000010;
+        /// Body of the function is being parsed right now
+        const BODY      = 0b000100;
+        /// Signed overflow wraps around
+        const WRAP      = 0b001000;
+        /// Signed overflow panics with the line, where it happened
+        const CHECKED   = 0b010000;
+        /// Only declared so far, the definition comes later
+        const PROTOTYPE = 0b100000;
+        /// Signed overflow panics, even in release builds
+        const TRAP      = 0b1000000;
+        /// Declared with `()`, so that nothing is known about the parameters
+        const UNPROTOTYPED = 0b10000000;
     }
 }
 
@@ -12,8 +25,14 @@ pub struct Function {
     pub ret: TypeID,
     pub args: usize,
     pub lets: Vec <Let>,
+    /// Length of `lets` at the start of each open block
+    pub scopes: Vec <usize>,
     pub attrs: Vec <String>,
-    pub flags: FnFlags
+    pub flags: FnFlags,
+    /// Position of the definition in the code, `None` for prototypes
+    pub defined_at: Option <usize>,
+    /// Called functions, whose safety is known only at the end of the file
+    pub pending: Vec <String>
 }
 
 impl Function {
@@ -22,24 +41,408 @@ impl Function {
         unsafe { &mut FNS }
     }
 
-    #[inline]
-    pub fn add(f: Function) {
+    /// Function made of its header, nothing is known about its body yet
+    pub fn new(name: &str, ret: TypeID, params: Vec <Let>, mut attrs: Vec <String>) -> Function {
+        if (1..attrs.len()).any(|i| attrs[i..].contains(&attrs[i - 1])) {
+            panic!("cannot have duplicate attributes")
+        }
+        if attrs.iter().filter(|x| ["%W", "%T", "%C"].contains(&x.as_str())).count() > 1 {
+            panic!("cannot have more than one overflow policy")
+        }
+
+        let mut flags = FnFlags::empty();
+        flags.insert(FnFlags::SAFE);
+        flags.insert(FnFlags::PUBLIC);
+
+        let mut i = 0;
+        while i < attrs.len() {
+            match attrs[i].as_str() {
+                "%U" => flags.remove(FnFlags::SAFE),
+                "%P" => flags.remove(FnFlags::PUBLIC),
+                "%W" => flags.insert(FnFlags::WRAP),
+                "%T" => flags.insert(FnFlags::TRAP),
+                "%C" => flags.insert(FnFlags::CHECKED),
+                _ => {
+                    i += 1;
+                    continue
+                }
+            }
+            attrs.remove(i);
+        }
+
+        Function {
+            name: name.to_string(),
+            ret,
+            args: params.len(),
+            lets: params,
+            scopes: Vec::new(),
+            attrs,
+            flags,
+            defined_at: None,
+            pending: Vec::new()
+        }
+    }
+
+    /// `int f(int);`, which lets `f` be called above its definition
+    pub fn prototype(mut f: Function) {
+        if f.flags.contains(FnFlags::UNPROTOTYPED) {
+            // the parameters are told by the definition, if it is in the file
+            if let Some(x) = Self::later().iter().find(|x| x.0 == f.name).and_then(|x| clang::prototype(&x.1).ok()) {
+                f.lets = x.lets;
+                f.args = x.args;
+                f.flags.remove(FnFlags::UNPROTOTYPED)
+            }
+        }
+
+        match Self::fns().iter_mut().find(|x| x.name == f.name) {
+            Some(x) => {
+                x.check_same(&f);
+                if x.flags.contains(FnFlags::UNPROTOTYPED) && !f.flags.contains(FnFlags::UNPROTOTYPED) {
+                    x.lets = f.lets;
+                    x.args = f.args;
+                    x.flags.remove(FnFlags::UNPROTOTYPED)
+                }
+            },
+            None => {
+                f.flags.insert(FnFlags::PROTOTYPE);
+                // the function being defined has to stay the last one
+                let at = Self::fns().len() - Self::is_in_body() as usize;
+                Self::fns().insert(at, f)
+            }
+        }
+    }
+
+    /// Starts the definition found at `pos`, which takes the place of the prototype if there is one
+    pub fn define(pos: usize, mut f: Function) {
+        if let Some(idx) = Self::fns().iter().position(|x| x.name == f.name) {
+            let old = Self::fns().remove(idx);
+            if old.defined_at.map_or(false, |x| x != pos) {
+                panic!("redefinition of function `{}`", f.name)
+            }
+            old.check_same(&f);
+
+            // callers have already relied on what the prototype said
+            f.flags.remove(!old.flags & (FnFlags::SAFE | FnFlags::PUBLIC));
+            f.flags.insert(old.flags & (FnFlags::WRAP | FnFlags::TRAP | FnFlags::CHECKED));
+            for attr in old.attrs {
+                if !f.attrs.contains(&attr) {
+                    f.attrs.push(attr)
+                }
+            }
+        }
+
+        f.flags.remove(FnFlags::PROTOTYPE);
+        f.flags.insert(FnFlags::BODY);
+        f.defined_at = Some(pos);
         Self::fns().push(f)
     }
 
+    /// Panics unless the declarations agree; `()` agrees with any parameters
+    fn check_same(&self, other: &Function) {
+        let is_unprototyped = (self.flags | other.flags).contains(FnFlags::UNPROTOTYPED);
+        if self.ret != other.ret || !is_unprototyped && self.args != other.args
+            || !is_unprototyped && self.lets[..self.args].iter().zip(&other.lets[..other.args]).any(|(x, y)| x.ty != y.ty) {
+            panic!("conflicting types for `{}`", self.name)
+        }
+    }
+
+    /// Headers of all functions defined in the file, found before parsing
+    fn later() -> &'static mut Vec <(String, String)> {
+        static mut LATER: Vec <(String, String)> = Vec::new();
+        unsafe { &mut LATER }
+    }
+
+    /// Remembers the header of every function definition, so calls above
+    /// the definition can be resolved without a prototype
+    pub fn scan(code: &str) {
+        let bytes = code.as_bytes();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    let quote = bytes[i];
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 }
+                    }
+                }
+                b'{' => {
+                    let header = code[start..i].trim_end();
+                    if depth == 0 && header.ends_with(')') {
+                        if let Some(name) = Self::name_of(header) {
+                            Self::later().push((name.to_string(), header.to_string()))
+                        }
+                    }
+                    depth += 1
+                }
+                b'}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        start = i + 1
+                    }
+                }
+                b';' if depth == 0 => start = i + 1,
+                _ => ()
+            }
+            i += 1
+        }
+    }
+
+    /// Name standing right before the parameters of `header`
+    fn name_of(header: &str) -> Option <&str> {
+        let mut depth = 0;
+        let open = header.char_indices().rev().find(|&(_, c)| {
+            match c {
+                ')' => depth += 1,
+                '(' => depth -= 1,
+                _ => ()
+            }
+            depth == 0
+        })?.0;
+        let before = header[..open].trim_end();
+        let name = &before[before.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_').len()..];
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Function called `name`; the ones defined later in the file are declared on the first call
+    pub fn find(name: &str) -> Option <&'static mut Function> {
+        if !Self::fns().iter().any(|x| x.name == name) {
+            let header = &Self::later().iter().find(|x| x.0 == name)?.1;
+            Self::prototype(clang::prototype(header).ok()?)
+        }
+        Self::fns().iter_mut().find(|x| x.name == name)
+    }
+
+    /// Forgets the functions of the files translated before
+    pub fn reset() {
+        Self::fns().clear();
+        Self::later().clear()
+    }
+
+    /// Whether the safety of the function is already known
+    fn is_resolved(&self) -> bool {
+        self.should_be_safe() || !self.flags.contains(FnFlags::SAFE)
+            || !self.flags.contains(FnFlags::PROTOTYPE) && self.pending.is_empty()
+    }
+
+    /// Opens `\u{7}caller\u{7}callee\u{7}` and closes `\u{8}` a call, which may require
+    /// `unsafe`, until the whole file is known
+    pub const CALL: char = '\u{7}';
+    pub const CALL_END: char = '\u{8}';
+    /// Stands around the name in place of `unsafe ` of a function calling such ones
+    pub const HEADER: char = '\u{e}';
+
+    /// `code` calling `callee`; the safety of functions, which are only declared
+    /// or call such ones, is decided in `resolve_calls`
+    pub fn call(callee: &str, code: String) -> String {
+        let f = Self::find(callee).unwrap();
+        if !f.is_resolved() {
+            let caller = Self::get();
+            caller.pending.push(callee.to_string());
+            format!("{0}{1}{0}{2}{0}{3}{4}", Self::CALL, caller.name, callee, code, Self::CALL_END)
+        } else if f.flags.contains(FnFlags::SAFE) {
+            code
+        } else {
+            Self::get().unsafe_code(code).parentify()
+        }
+    }
+
+    /// Header of the function being defined: `unsafe ` if it's known to be unsafe,
+    /// a placeholder if it depends on the functions defined later
+    pub fn header(&self) -> String {
+        if !self.flags.contains(FnFlags::SAFE) {
+            String::from("unsafe ")
+        } else if self.pending.is_empty() || self.should_be_safe() {
+            String::new()
+        } else {
+            format!("{}{}{0}", Self::HEADER, self.name)
+        }
+    }
+
+    /// `extern` blocks of the functions, which are declared, but not defined in the file,
+    /// so they are left to the linker; the ones marked as safe are called through a safe wrapper
+    pub fn lower_externs() -> String {
+        let mut s = String::new();
+        for f in Self::fns().iter().filter(|x| x.flags.contains(FnFlags::PROTOTYPE)) {
+            let public = if f.flags.contains(FnFlags::PUBLIC) { "pub " } else { "" };
+            let ret = if f.attrs.iter().any(|x| x == "%N") {
+                String::from(" -> !")
+            } else if f.ret == BuiltinType::Void.as_id() {
+                String::new()
+            } else {
+                format!(" -> {}", FullType::real(&f.ret))
+            };
+            let names: Vec <String> = f.lets[..f.args].iter().enumerate().map(|(i, x)| if x.name.is_empty() {
+                format!("__arg{}", i)
+            } else {
+                x.real.clone()
+            }).collect();
+            let params = names.iter().zip(&f.lets).map(|(x, y)| format!("{}: {}", x, FullType::real(&y.ty))).collect::<Vec <_>>().join(", ");
+
+            if f.should_be_safe() {
+                s.push_str(&format!("\nextern \"C\" {{\n\t#[link_name = \"{name}\"]\n\tfn __qas_extern_{name}({params}){ret};\n}}\n\
+                    \n{public}fn {name}({params}){ret} {{\n\tunsafe {{ __qas_extern_{name}({}) }}\n}}\n",
+                    names.join(", "), name = f.name, params = params, ret = ret, public = public))
+            } else {
+                s.push_str(&format!("\nextern \"C\" {{\n\t{}fn {}({}){};\n}}\n", public, f.name, params, ret))
+            }
+        }
+        s
+    }
+
+    /// Decides the safety of the functions left pending by `call` now that the whole file is known:
+    /// the ones, which are never defined, are unsafe unless marked as safe, and so are their callers
+    pub fn resolve_calls(code: String) -> String {
+        let mut unsafe_fns: Vec <&str> = Self::fns().iter().filter(|x| !x.should_be_safe()
+            && (!x.flags.contains(FnFlags::SAFE) || x.flags.contains(FnFlags::PROTOTYPE))).map(|x| x.name.as_str()).collect();
+        loop {
+            let more: Vec <&str> = Self::fns().iter().filter(|x| !x.should_be_safe() && !unsafe_fns.contains(&x.name.as_str())
+                && x.pending.iter().any(|y| unsafe_fns.contains(&y.as_str()))).map(|x| x.name.as_str()).collect();
+            if more.is_empty() {
+                break
+            }
+            unsafe_fns.extend(more)
+        }
+
+        let mut s = String::new();
+        // whether each open call is wrapped into `unsafe` block
+        let mut wrapped = Vec::new();
+        let mut rest = code.as_str();
+        while let Some(i) = rest.find(|c| c == Self::CALL || c == Self::CALL_END || c == Self::HEADER) {
+            s.push_str(&rest[..i]);
+            let c = rest[i..].chars().next().unwrap();
+            rest = &rest[i + c.len_utf8()..];
+
+            if c == Self::CALL {
+                let (caller, tail) = rest.split_once(Self::CALL).unwrap();
+                let (callee, tail) = tail.split_once(Self::CALL).unwrap();
+                rest = tail;
+                // the caller, which isn't marked as safe, just becomes unsafe itself
+                let wrap = unsafe_fns.contains(&callee) && !wrapped.contains(&true)
+                    && Self::fns().iter().any(|x| x.name == caller && x.should_be_safe());
+                if wrap {
+                    s.push_str("(unsafe { ")
+                }
+                wrapped.push(wrap)
+            } else if c == Self::CALL_END {
+                if wrapped.pop().unwrap() {
+                    s.push_str(" })")
+                }
+            } else {
+                let (name, tail) = rest.split_once(Self::HEADER).unwrap();
+                rest = tail;
+                if unsafe_fns.contains(&name) {
+                    s.push_str("unsafe ")
+                }
+            }
+        }
+        s.push_str(rest);
+        s
+    }
+
     #[inline]
     pub fn get() -> &'static mut Function {
         Self::fns().last_mut().unwrap()
     }
 
-    pub fn type_of_let(&self, name: &str) -> Option <&TypeID> {
-        self.lets.iter().find(|x| x.name == name).map(|x| &x.ty)
+    pub fn is_in_body() -> bool {
+        Self::fns().last().map_or(false, |x| x.flags.contains(FnFlags::BODY))
+    }
+
+    /// Finds the innermost visible variable called `name`
+    pub fn find_let(&mut self, name: &str) -> Option <&mut Let> {
+        self.lets.iter_mut().rev().find(|x| x.alive && x.name == name)
+    }
+
+    pub fn enter(&mut self) {
+        self.scopes.push(self.lets.len())
+    }
+
+    pub fn leave(&mut self) {
+        let start = self.scopes.pop().unwrap();
+        for x in self.lets[start..].iter_mut() {
+            x.alive = false
+        }
+    }
+
+    /// Declares a local variable and returns its `let` statement
+    pub fn declare(&mut self, name: &str, ty: TypeID, init: Option <Init>) -> String {
+        let start = *self.scopes.last().unwrap_or(&self.args);
+        if self.lets[start..].iter().any(|x| x.alive && x.name == name) {
+            panic!("redefinition of `{}`", name)
+        }
+
+        let value = match init {
+            Some(x) => x.lower(&ty),
+            None => String::from("::qas::builtin::zeroed()")
+        };
+        let real = FullType::real(&ty);
+
+        let mut x = Let::new(name, ty);
+        if self.lets.iter().any(|y| y.alive && y.real == x.real) {
+            // declarations may be hoisted by `goto`, so that the shadowed variable must keep its name
+            let base = x.real.clone();
+            let mut i = 0;
+            while self.lets.iter().any(|y| y.real == x.real || y.name == x.real) || Global::find(&x.real).is_some() {
+                i += 1;
+                x.real = format!("{}_{}", base, i)
+            }
+        }
+        self.lets.push(x);
+        format!("let {}{}: {} = {};", Let::marker(self.lets.len() - 1), self.lets.last().unwrap().real, real, value)
+    }
+
+    /// `static` variable, which is called `real` outside of the function
+    pub fn declare_static(&mut self, name: &str, ty: TypeID, real: String) {
+        let start = *self.scopes.last().unwrap_or(&self.args);
+        if self.lets[start..].iter().any(|x| x.alive && x.name == name) {
+            panic!("redefinition of `{}`", name)
+        }
+        self.lets.push(Let {
+            real,
+            ..Let::new(name, ty)
+        })
+    }
+
+    /// Puts `mut` where it is required now that the whole body is known
+    pub fn resolve_lets(&self, mut body: String) -> String {
+        for (idx, x) in self.lets.iter().enumerate().skip(self.args) {
+            body = body.replace(&Let::marker(idx), if x.mutable { "mut " } else { "" })
+        }
+        body
     }
 
     pub fn should_be_safe(&self) -> bool {
         self.attrs.iter().find(|x| *x == "%S").is_some()
     }
 
+    /// Stands before code, which requires `unsafe`, until the whole body is known
+    pub const UNSAFE: &'static str = "\u{4}";
+
+    /// Code, which requires `unsafe`: it is wrapped into `unsafe` block if the function
+    /// has to stay safe, otherwise the function becomes unsafe in `resolve_unsafe`
+    pub fn unsafe_code(&self, code: String) -> String {
+        if self.should_be_safe() {
+            format!("unsafe {{ {} }}", code)
+        } else {
+            format!("{}{}", Self::UNSAFE, code)
+        }
+    }
+
+    pub fn resolve_unsafe(&mut self, body: String) -> String {
+        if body.contains(Self::UNSAFE) {
+            self.flags.remove(FnFlags::SAFE)
+        }
+        body.replace(Self::UNSAFE, "")
+    }
+
     pub fn as_builtin(&'static self) -> BuiltinFunction {
         BuiltinFunction {
             name: "",
@@ -49,21 +452,121 @@ impl Function {
         }
     }
 
+    /// What an operand of `sizeof`, which is never evaluated, may change in the function being defined
+    pub fn snapshot() -> Option <Snapshot> {
+        if !Self::is_in_body() {
+            return None
+        }
+        let f = Self::get();
+        Some(Snapshot {
+            flags: f.flags,
+            mutable: f.lets.iter().map(|x| x.mutable).collect(),
+            pending: f.pending.len()
+        })
+    }
+
+    pub fn restore(snapshot: Option <Snapshot>) {
+        if let Some(x) = snapshot {
+            let f = Self::get();
+            f.flags = x.flags;
+            for (x, mutable) in f.lets.iter_mut().zip(x.mutable) {
+                x.mutable = mutable
+            }
+            f.pending.truncate(x.pending)
+        }
+    }
+
     pub fn check_and_make_mutable_on_require(name: &str) {
-        match Self::get().lets.iter_mut().find(|x| x.name == name) {
+        // fields and elements are changed through the variable holding them
+        let name = name.split(|x| x == '.' || x == '[').next().unwrap();
+        match Self::get().lets.iter_mut().rev().find(|x| x.alive && x.real == name) {
             Some(x) => x.mutable = true,
             None => ()
         }
     }
 }
 
+pub struct Snapshot {
+    flags: FnFlags,
+    mutable: Vec <bool>,
+    pending: usize
+}
+
 #[derive(Debug)]
 pub struct Let {
     pub name: String,
+    /// Name in Rust, which differs only for the variables shadowing statics
+    pub real: String,
     pub mutable: bool,
+    /// `false` once the block declaring it is closed
+    pub alive: bool,
     pub ty: TypeID
 }
 
+impl Let {
+    pub fn new(name: &str, ty: TypeID) -> Let {
+        Let {
+            name: name.to_string(),
+            // Rust does not let variables shadow statics
+            real: if Global::find(name).is_some() {
+                format!("{}_local", name)
+            } else {
+                name.to_string()
+            },
+            mutable: false,
+            alive: true,
+            ty
+        }
+    }
+
+    /// Stands in place of `mut ` until `Function::resolve_lets`
+    pub fn marker(idx: usize) -> String {
+        format!("\u{1}{}\u{1}", idx)
+    }
+}
+
+/// Placeholders for `break`, `continue` and `goto`, replaced by the innermost
+/// enclosing construct which handles them once it is parsed
+pub struct Jump;
+
+impl Jump {
+    pub const BREAK: &'static str = "\u{2}break\u{2}";
+    pub const CONTINUE: &'static str = "\u{2}continue\u{2}";
+
+    /// Unique number for Rust labels
+    pub fn label() -> usize {
+        static mut LABEL: usize = 0;
+        unsafe {
+            LABEL += 1;
+            LABEL
+        }
+    }
+
+    /// `goto name;`, resolved by the block containing the label
+    pub fn goto(name: &str) -> String {
+        format!("\u{3}goto {}\u{3}", name)
+    }
+
+    /// `name:`, which precedes the labeled statement
+    pub fn target(name: &str) -> String {
+        format!("\u{3}{}:\u{3}", name)
+    }
+
+    pub fn check_resolved(body: &str) {
+        if body.contains(Self::BREAK) {
+            panic!("`break` outside of loop or switch")
+        } else if body.contains(Self::CONTINUE) {
+            panic!("`continue` outside of loop")
+        } else if let Some(idx) = body.find("\u{3}goto ") {
+            let name = &body[idx + 6..];
+            panic!("`goto {}` jumps to unknown label or into a nested block", &name[..name.find('\u{3}').unwrap()])
+        } else if let Some(idx) = body.find('\u{3}') {
+            let name = &body[idx + 1..];
+            panic!("label `{}` is not directly inside a block", &name[..name.find(':').unwrap()])
+        }
+    }
+}
+
 pub struct Tab;
 
 impl Tab {
@@ -73,7 +576,7 @@ impl Tab {
     }
 
     pub fn tabs_num() -> usize {
-        *Self::number_tabs() + Function::fns().len()
+        *Self::number_tabs() + Function::is_in_body() as usize
     }
 
     pub fn tabs() -> String {
@@ -83,4 +586,14 @@ impl Tab {
     pub fn tabs_nl() -> String {
         String::from("\n") + &Self::tabs()
     }
+
+    /// Shifts already formatted code one level right
+    pub fn indent(code: &str) -> String {
+        code.replace('\n', "\n\t")
+    }
+
+    /// Shifts already formatted code one level left
+    pub fn dedent(code: &str) -> String {
+        code.replace("\n\t", "\n")
+    }
 }
@@ -0,0 +1,182 @@
+use super::{Expr, TypeID, FullType, Function, Assign, Record, Init};
+
+/// How a variable at file scope is lowered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Storage {
+    /// `const` scalar, which is just a value
+    Const,
+    /// `static`, which is never changed
+    Static,
+    /// `static mut`, which may be accessed only inside of `unsafe`
+    StaticMut
+}
+
+#[derive(Debug)]
+pub struct Global {
+    pub name: String,
+    pub ty: TypeID,
+    pub storage: Storage,
+    pub public: bool,
+    /// `false` for `extern` declaration, until the definition is met
+    pub defined: bool,
+    pub init: Option <String>,
+    /// `static` inside of function, which is reachable only through its `Let`
+    pub is_in_function: bool,
+    /// Position of the declaration, which registered the variable
+    pos: usize
+}
+
+impl Global {
+    pub fn globals() -> &'static mut Vec <Global> {
+        static mut GLOBALS: Vec <Global> = Vec::new();
+        unsafe { &mut GLOBALS }
+    }
+
+    pub fn find(name: &str) -> Option <&'static Global> {
+        Self::globals().iter().find(|x| x.name == name && !x.is_in_function)
+    }
+
+    /// Whether an initializer is being parsed, so that it must be constant
+    pub fn is_constant() -> &'static mut bool {
+        static mut IS_CONSTANT: bool = false;
+        unsafe { &mut IS_CONSTANT }
+    }
+
+    fn storage(ty: &TypeID) -> Storage {
+        if ty.mutable {
+            Storage::StaticMut
+        } else if ty.dims.is_empty() && !Record::is_record(ty) {
+            Storage::Const
+        } else if !Self::has_pointer(ty) {
+            Storage::Static
+        } else {
+            // raw pointers are not `Sync`
+            Storage::StaticMut
+        }
+    }
+
+    /// Declaration found at `pos`: `extern` one only tells the type,
+    /// while the rest define the variable, initialized or not
+    pub fn declare(pos: usize, storage: Option <&str>, name: &str, ty: TypeID, init: Option <Init>) {
+        let is_extern = storage == Some("extern") && init.is_none();
+        let init = init.map(|x| x.lower(&ty));
+
+        let x = match Self::globals().iter_mut().find(|x| x.name == name) {
+            Some(x) if x.pos == pos => return,
+            Some(x) => x,
+            None => {
+                Self::globals().push(Global {
+                    name: name.to_string(),
+                    storage: if is_extern {
+                        Storage::StaticMut
+                    } else {
+                        Self::storage(&ty)
+                    },
+                    ty,
+                    public: storage != Some("static"),
+                    defined: !is_extern,
+                    init,
+                    is_in_function: false,
+                    pos
+                });
+                return
+            }
+        };
+
+        if x.ty != ty || x.ty.mutable != ty.mutable {
+            panic!("conflicting types for `{}`", name)
+        } else if x.init.is_some() && init.is_some() {
+            panic!("redefinition of `{}`", name)
+        }
+
+        x.public &= storage != Some("static");
+        x.defined |= !is_extern;
+        if init.is_some() {
+            x.init = init
+        }
+    }
+
+    /// `static` declared at `pos` inside of the current function, which gets
+    /// a name unique in the file; the name is returned
+    pub fn declare_in_function(pos: usize, name: &str, ty: TypeID, init: Option <Init>) -> String {
+        if let Some(x) = Self::globals().iter().find(|x| x.pos == pos && x.is_in_function) {
+            return x.name.clone()
+        }
+
+        // the prefix is reserved, so that the name never clashes with names of the file
+        let base = format!("__qas_static_{}_{}", Function::get().name, name);
+        let mut real = base.clone();
+        let mut i = 0;
+        while Self::globals().iter().any(|x| x.name == real) {
+            i += 1;
+            real = format!("{}_{}", base, i)
+        }
+
+        Self::globals().push(Global {
+            name: real.clone(),
+            storage: Self::storage(&ty),
+            init: init.map(|x| x.lower(&ty)),
+            ty,
+            public: false,
+            defined: true,
+            is_in_function: true,
+            pos
+        });
+        real
+    }
+
+    fn has_pointer(ty: &TypeID) -> bool {
+        if !ty.ptr.is_empty() {
+            true
+        } else if Record::is_record(ty) {
+            FullType::types()[ty.idx].fields.iter().any(|x| Self::has_pointer(&x.ty))
+        } else {
+            false
+        }
+    }
+
+    /// Whether `place` is a part of `static mut`, so that it must be accessed inside of `unsafe`
+    pub fn is_static_mut(place: &str) -> bool {
+        let name = place.split(|x| x == '.' || x == '[').next().unwrap();
+        Self::globals().iter().any(|x| x.name == name && x.storage == Storage::StaticMut)
+    }
+
+    /// Raw pointer to `place`, which does not create a reference to `static mut`
+    pub fn address(place: &str, mutable: bool) -> String {
+        if mutable {
+            format!("::core::ptr::addr_of_mut!({})", place)
+        } else {
+            format!("::core::ptr::addr_of!({})", place)
+        }
+    }
+
+    /// Variable used in expression
+    pub fn to_expr(&self) -> Expr {
+        let mut x = Expr::lvalue(String::new(), self.name.clone(), &self.ty);
+        x.name = Assign::read(&x);
+        x
+    }
+
+    /// Items of all variables, which are taken away from the list;
+    /// `extern` ones not defined in the file are left to the linker
+    pub fn lower_all() -> String {
+        let mut s = String::new();
+        for x in std::mem::take(Self::globals()) {
+            let real = FullType::real(&x.ty);
+            let public = if x.public { "pub " } else { "" };
+            s.push_str("\n#[allow(non_upper_case_globals)]\n");
+            if !x.defined {
+                s.push_str(&format!("extern \"C\" {{\n\t{}static mut {}: {};\n}}\n", public, x.name, real));
+                continue
+            }
+
+            let value = x.init.unwrap_or_else(|| String::from("::qas::builtin::zeroed()"));
+            s.push_str(&format!("{}{} {}: {} = {};\n", public, match x.storage {
+                Storage::Const => "const",
+                Storage::Static => "static",
+                Storage::StaticMut => "static mut"
+            }, x.name, real, value))
+        }
+        s
+    }
+}
@@ -4,6 +4,11 @@ mod op;
 mod preprocessor;
 mod ty;
 mod comment;
+mod constant;
+mod flow;
+mod record;
+mod array;
+mod global;
 
 use crate::StringExt;
 use check_keyword::CheckKeyword;
@@ -14,17 +19,33 @@ use op::*;
 use ty::*;
 use preprocessor::*;
 use comment::*;
+use constant::*;
+use flow::*;
+use record::*;
+use array::*;
+use global::*;
 
 #[derive(Debug)]
 pub struct Expr {
     pub name: String,
-    pub ty: TypeID
+    pub ty: TypeID,
+    /// How to refer to the object designated by expression, if it is an lvalue
+    pub place: Option <String>,
+    /// Whether the place is inside of union, so that only writing it is safe
+    pub union: bool,
+    /// Whether it is a value cast to `void`, which `name` throws away in a block
+    pub void_cast: bool
 }
 
 impl Expr {
     #[inline]
     pub fn new(name: String, ty: &TypeID) -> Self {
-        Self { name, ty: ty.clone() }
+        Self { name, ty: ty.clone(), place: None, union: false, void_cast: false }
+    }
+
+    #[inline]
+    pub fn lvalue(name: String, place: String, ty: &TypeID) -> Self {
+        Self { name, ty: ty.clone(), place: Some(place), union: false, void_cast: false }
     }
 
     pub fn convert(&mut self, to: &TypeID) {
@@ -33,6 +54,14 @@ impl Expr {
     }
 }
 
+/// Operator written after an expression
+pub enum Postfix <'a> {
+    Member(&'a str),
+    Arrow(&'a str),
+    Index(Expr),
+    Unop(&'static str)
+}
+
 pub struct BitPtrIter;
 
 impl BitPtrIter {
@@ -47,6 +76,12 @@ impl BitPtrIter {
     }
 }
 
+/// Words, which cannot name variables or functions
+const KEYWORDS: &[&str] = &[
+    "break", "case", "continue", "default", "do", "else", "enum", "for", "goto", "if",
+    "return", "sizeof", "struct", "switch", "typedef", "union", "while"
+];
+
 peg::parser! { pub grammar clang() for str {
     rule digit2() -> &'input str
         = x:$(['0' | '1'])
@@ -66,15 +101,42 @@ peg::parser! { pub grammar clang() for str {
     rule name() -> &'input str
         = x:$(letter() (letter() / digit10())*) { x }
 
-    rule __num_suffix() -> &'static str
-        = $("l" / "L" / "ll" / "LL") { "i64" }
-        / $("u" / "U") { "u64" }
+    /// Integer suffix: whether it has `u` and whether it has `l` or `ll`
+    rule __int_suffix() -> (bool, bool)
+        = $(['u' | 'U']) long:$("ll" / "LL" / ['l' | 'L'])? { (true, long.is_some()) }
+        / $("ll" / "LL" / ['l' | 'L']) unsigned:$(['u' | 'U'])? { (unsigned.is_some(), true) }
 
     rule num() -> String
-        = "0b" num:$(digit2()+) suffix:__num_suffix()? { String::from("0b") + num + suffix.unwrap_or_default() }
-        / "0o" num:$(digit8()+) suffix:__num_suffix()? { String::from("0o") + num + suffix.unwrap_or_default() }
-        / "0x" num:$(digit16()+) suffix:__num_suffix()? { String::from("0x") + num + suffix.unwrap_or_default() }
-        / num:$(digit10()+ "."? digit10()*) suffix:__num_suffix()? { num.to_string() + suffix.unwrap_or_default() }
+        = "0" ['b' | 'B'] num:$(digit2()+) { String::from("0b") + num }
+        / "0o" num:$(digit8()+) { String::from("0o") + num }
+        / "0" ['x' | 'X'] num:$(digit16()+) { String::from("0x") + num }
+        / "0" num:$(digit10()+) {
+            if !num.chars().all(|x| ('0'..='7').contains(&x)) {
+                panic!("invalid digit in octal constant `0{}`", num)
+            }
+            String::from("0o") + num
+        }
+        / num:$(digit10()+) { num.to_string() }
+
+    rule __float_exp() -> &'input str = x:$(['e' | 'E'] ['+' | '-']? digit10()+) { x }
+
+    /// Floating literal written as Rust literal
+    rule float() -> String
+        = "0" ['x' | 'X'] m:$(digit16()* "." digit16()* / digit16()+) ['p' | 'P'] e:$(['+' | '-']? digit10()+) {?
+            if m == "." {
+                return Err("hexadecimal digit")
+            }
+            Ok(Constant::hex_float(m, e))
+        }
+        / m:$(digit10()+ "." digit10()* / "." digit10()+) e:__float_exp()? {
+            format!("{}{}{}{}", if m.starts_with('.') { "0" } else { "" }, m, if m.ends_with('.') { "0" } else { "" }, e.unwrap_or_default())
+        }
+        / m:$(digit10()+) e:__float_exp() { format!("{}{}", m, e) }
+
+    /// Number with its C type
+    rule literal() -> Expr
+        = x:float() suffix:$(['f' | 'F' | 'l' | 'L'])? !(letter() / digit10()) { Operand::parse_to_float(x, suffix) }
+        / x:num() suffix:__int_suffix()? !(letter() / digit10()) { Operand::parse_to_num(x, suffix) }
 
     rule punct() -> &'input str
         = x:$(['+' | '-' | '*' | '/' | '%' | '&' | '|' | '=' | '<' | '>' | '!' | '^' | '~']*)
@@ -82,19 +144,43 @@ peg::parser! { pub grammar clang() for str {
     rule __string_suffix() -> bool
         = x:"L"? { x.is_none() }
 
-    rule __string_one(ext: bool) -> String = precedence! {
-        "\\\"" { String::from("\"") }
-        x:$([^ '\"']) {
+    /// Escape sequence after backslash and the code it stands for
+    rule __escape() -> u32
+        = x:$(digit8()*<1,3>) { u32::from_str_radix(x, 8).unwrap() }
+        / "x" x:$(digit16()+) { u32::from_str_radix(x, 16).unwrap_or(u32::MAX) }
+        / x:$([_]) { Constant::escape(x) }
+
+    rule __string_one(ext: bool) -> String
+        = "\\" x:__escape() { Constant::string_char(x, ext) }
+        / x:$([^ '\"' | '\\' | '\n']) {
             if !x.is_ascii() && ext {
                 panic!("non-ascii character are not allowed in strings; try using `L` suffix to support it")
             }
-            x.to_string()
+            Constant::string_char(x.chars().next().unwrap() as u32, ext)
         }
-    }
 
+    /// String literal written as Rust string literal without quotes
     rule string() -> String
         = suffix:__string_suffix() "\"" x:__string_one(suffix)* "\"" { x.join("") + "\0" }
 
+    /// String, which is not a C literal, but a path or Rust code
+    rule raw_string() -> String
+        = "\"" x:$(("\\\"" / [^ '\"' | '\n'])*) "\"" { x.replace("\\\"", "\"") }
+
+    rule __char_one(ext: bool) -> u32
+        = "\\" x:__escape() { x }
+        / x:$([^ '\'' | '\\' | '\n']) {
+            if !x.is_ascii() && ext {
+                panic!("non-ascii character are not allowed in character constants; try using `L` prefix to support it")
+            }
+            x.chars().next().unwrap() as u32
+        }
+
+    /// Character constant, which is `int`
+    rule char_literal() -> u32 = suffix:__string_suffix() "'" x:__char_one(suffix)+ "'" {
+        Constant::character(x, suffix)
+    }
+
     rule newline() = "\n" {
         unsafe { LINE += 1 }
     }
@@ -132,7 +218,7 @@ peg::parser! { pub grammar clang() for str {
         / ("unsigned" _ "int" / "unsigned") { BuiltinType::UnsignedInt }
 
     pub(in self) rule try_ty() -> Result <usize, &'input str> = precedence! {
-        x:__builtin_try_ty() { Ok(x as usize) }
+        x:__builtin_try_ty() !(letter() / digit10()) { Ok(x as usize) }
 
         x:name() {
             let mut i = BuiltinType::Count as usize;
@@ -147,19 +233,80 @@ peg::parser! { pub grammar clang() for str {
 
     rule __ty_ptr() -> bool = _ "*" c:$(_ "const")? { c.is_none() }
 
-    rule ty() -> TypeID = x:$("const" __)? ty:try_ty() ptr:__ty_ptr()* _ {?
+    rule __record_declarator(base: &TypeID) -> Field = ptr:__ty_ptr()* _ name:var() _ dims:__dims() {
+        Field {
+            name: name.to_string(),
+            ty: base.with_ptr(ptr).with_dims(dims.into_iter().map(|x| x.unwrap_or_else(|| panic!("flexible array members are not supported"))).collect())
+        }
+    }
+
+    rule __record_field() -> Vec <Field>
+        = base:__ty_base() _ fields:__record_declarator((&base)) ++ ("," _) ";" _ { fields }
+        / base:__ty_base() _ ";" _ {?
+            if Record::is_anonymous(&base) {
+                Ok(vec![Field { name: String::new(), ty: base }])
+            } else {
+                Err("member name")
+            }
+        }
+
+    rule __record_begin(pos: usize, kind: &str, name: Option <&str>) -> usize = "" {
+        Record::begin(pos, kind, name)
+    }
+
+    rule __record_kind() -> &'input str = kind:$("struct" / "union") __kw_end() { kind }
+
+    /// Struct, union or enum definition or declaration without any variables
+    rule __record_decl()
+        = ("const" __)? __enum() _ ";" _
+        / base:__ty_base() _ ";" _ {?
+        if Record::is_record(&base) {
+            Ok(())
+        } else {
+            Err("declaration")
+        }
+    }
+
+    rule __record() -> usize
+        = kind:__record_kind() _ pos:position!() name:name()? _ "{" _ idx:__record_begin(pos, kind, name) fields:__record_field()* "}" {
+            Record::define(idx, fields.into_iter().flatten().collect())
+        }
+        / kind:__record_kind() _ name:name() { Record::tagged(kind, name) }
+
+    rule __enum_begin() = "" {
+        Enumerator::begin()
+    }
+
+    rule __enumerator() = pos:position!() name:var() _ value:("=" _ x:const_expr() _ { x })? {
+        Enumerator::add(pos, name, value)
+    }
+
+    /// Enumerations are `int`, while enumerators are `const` items
+    rule __enum() -> TypeID
+        = "enum" __kw_end() _ pos:position!() name:name()? _ "{" _ __enum_begin() __enumerator() ++ ("," _) ("," _)? "}" {
+            if let Some(name) = name {
+                Enumerator::tag(pos, name)
+            }
+            BuiltinType::SignedInt.as_id()
+        }
+        / "enum" __kw_end() _ name:name() {
+            match AliasType::find(&format!("enum {}", name)) {
+                Some(id) => id.clone(),
+                None => panic!("unknown `enum {}`", name)
+            }
+        }
+
+    rule __ty_base() -> TypeID
+        = x:$("const" __)? idx:__record() { TypeID::from(idx, x.is_none()) }
+        / x:$("const" __)? ty:__enum() { TypeID { mutable: x.is_none(), ..ty } }
+        / x:$("const" __)? ty:try_ty() {?
         match ty {
-            Ok(idx) => Ok(TypeID {
-                idx,
-                ptr: BitPtrIter::append_many(Default::default(), ptr),
-                mutable: x.is_none()
-            }),
+            Ok(idx) => Ok(TypeID::from(idx, x.is_none())),
             Err(name) => {
                 match AliasType::find(name) {
                     Some(id) => Ok(TypeID {
-                        idx: id.idx,
-                        ptr: BitPtrIter::append_many(id.ptr.clone(), ptr),
-                        mutable: id.mutable && x.is_none()
+                        mutable: id.mutable && x.is_none(),
+                        ..id.clone()
                     }),
                     None => Err("expected type")
                 }
@@ -167,7 +314,14 @@ peg::parser! { pub grammar clang() for str {
         }
     }
 
+    rule ty() -> TypeID = base:__ty_base() ptr:__ty_ptr()* _ {
+        base.with_ptr(ptr)
+    }
+
     rule var() -> &'input str = x:name() {?
+        if KEYWORDS.contains(&x) {
+            return Err("variable")
+        }
         match try_ty(&x) {
             Ok(id) => match id {
                 Ok(_) => Err(""),
@@ -181,12 +335,20 @@ peg::parser! { pub grammar clang() for str {
         expr
     }
 
+    /// Type without a name, such as `int *[3]`
+    rule type_name() -> TypeID = ty:ty() _ dims:__dims() {
+        ty.with_dims(dims.into_iter().map(|x| x.expect("array size is missing")).collect())
+    }
+
     rule __sizeof() -> TypeID
         = __ ty:ty() { ty }
-        / _ "(" _ ty:ty() _ ")" { ty }
+        / _ "(" _ ty:type_name() _ ")" { ty }
+
+    /// State of the function before an operand of `sizeof`, so that it can be put back
+    rule __snapshot() -> Option <Snapshot> = { Function::snapshot() }
 
-    rule _e_num() -> Expr = i:num() {
-        Operand::parse_to_num(i)
+    rule _e_num() -> Expr = i:literal() {
+        i
     }
 
     rule _e_var() -> Expr = i:var() {
@@ -198,19 +360,30 @@ peg::parser! { pub grammar clang() for str {
             Expr::new(format!("::qas::builtin::sizeof::<{}>()", FullType::real(&ty)), &BuiltinType::usized().as_id())
         }
 
-        i:_e_e0() _ "++" {
-            Unop::union(i, "++a")
+        "sizeof" _ "(" _ s:__snapshot() x:_e_object() _ ")" {
+            Function::restore(s);
+            Expr::new(format!("::qas::builtin::sizeof::<{}>()", FullType::real(&x.ty)), &BuiltinType::usized().as_id())
         }
 
-        i:_e_e0() _ "--" {
-            Unop::union(i, "--a")
+        "sizeof" _ "(" _ s:__snapshot() x:expr() _ ")" {
+            Function::restore(s);
+            Expr::new(format!("::qas::builtin::sizeof::<{}>()", FullType::real(&x.ty)), &BuiltinType::usized().as_id())
         }
 
-        "++" _ i:_e_e0() {
+        "sizeof" __ s:__snapshot() x:_e_object() {
+            Function::restore(s);
+            Expr::new(format!("::qas::builtin::sizeof::<{}>()", FullType::real(&x.ty)), &BuiltinType::usized().as_id())
+        }
+
+        "(" _ ty:type_name() _ ")" _ i:_e_e1() {
+            Unop::cast(i, ty)
+        }
+
+        "++" _ i:_e_e1() {
             Unop::union(i, "++b")
         }
 
-        "--" _ i:_e_e0() {
+        "--" _ i:_e_e1() {
             Unop::union(i, "--b")
         }
 
@@ -222,7 +395,7 @@ peg::parser! { pub grammar clang() for str {
             Unop::union(i, "-")
         }
 
-        "&" _ i:_e_e0() {
+        "&" _ i:_e_object() {
             Unop::union(i, "&")
         }
 
@@ -240,12 +413,18 @@ peg::parser! { pub grammar clang() for str {
     }
 
     rule _e_parens() -> Expr = "(" _ i:expr() _ ")" {
-        Expr::new(i.name.parentify(), &i.ty)
+        Expr {
+            name: i.name.parentify(),
+            ..i
+        }
     }
 
     rule _e_e0() -> Expr
         = name:var() _ "(" _ arg0:expr()? _ args:__expr_arg()* ("," _)? ")" {
-            let f = &Function::fns().iter().find(|x| x.name == name).map(|x| x.as_builtin()).or_else(|| BuiltinFunction::fns().iter().find(|x| x.name == name).map(Clone::clone)).expect("unknown function");
+            if *Global::is_constant() {
+                panic!("initializer element is not constant")
+            }
+            let f = &Function::find(name).map(|x| x.as_builtin()).or_else(|| BuiltinFunction::fns().iter().find(|x| x.name == name).map(Clone::clone)).expect("unknown function");
             let mut args = args;
 
             match arg0 {
@@ -261,7 +440,7 @@ peg::parser! { pub grammar clang() for str {
                 panic!("lack of args")
             }
 
-            Expr::new(format!("{}({})", f.real, {
+            let call = format!("{}({})", f.real, {
                 let mut s = String::new();
                 let mut i = 0;
                 while i < args.len() {
@@ -273,29 +452,58 @@ peg::parser! { pub grammar clang() for str {
                     s.pop(); // erase ','
                 }
                 s
-            }), &f.ret)
+            });
+
+            Expr::new(if Function::find(name).is_some() {
+                Function::call(name, call)
+            } else {
+                call
+            }, &f.ret)
         }
         / i:_e_num() { i }
+        / x:char_literal() { Expr::new(x.to_string(), &BuiltinType::SignedInt.as_id()) }
         / i:string() {
             Expr::new(format!("\"{}\"", i), &TypeID {
                 idx: BuiltinType::UnsignedChar as usize,
                 ptr: BitPtrIter::append(Default::default(), false),
-                mutable: false
+                mutable: false,
+                dims: Vec::new()
             })
         }
         / "__func__" {
             Expr::new(format!("\"{}\0\"", Function::get().name), &TypeID {
                 idx: BuiltinType::UnsignedChar as usize,
                 ptr: BitPtrIter::append(Default::default(), false),
-                mutable: false
+                mutable: false,
+                dims: Vec::new()
             })
         }
         / i:_e_var() { i }
 
-    rule _e_e1() -> Expr
-        = i:_e_parens() { i }
-        / i:_e_unop() { i }
-        / i:_e_e0()  { i }
+    rule __postfix() -> Postfix <'input>
+        = _ "." _ name:name() { Postfix::Member(name) }
+        / _ "->" _ name:name() { Postfix::Arrow(name) }
+        / _ "[" _ i:expr() _ "]" { Postfix::Index(i) }
+        / _ "++" { Postfix::Unop("++a") }
+        / _ "--" { Postfix::Unop("--a") }
+
+    rule _e_postfix() -> Expr = x:(_e_parens() / _e_e0()) ops:__postfix()* {
+        ops.into_iter().fold(x, |x, op| match op {
+            Postfix::Member(name) => Record::member(x, name),
+            Postfix::Arrow(name) => Record::arrow(Array::decay(x), name),
+            Postfix::Index(i) => Array::index(x, i),
+            Postfix::Unop(op) => Unop::union(x, op)
+        })
+    }
+
+    /// Expression, which is not turned from array into pointer yet
+    rule _e_object() -> Expr
+        = i:_e_unop() { i }
+        / i:_e_postfix()  { i }
+
+    rule _e_e1() -> Expr = i:_e_object() {
+        Array::decay(i)
+    }
 
     rule _e_o1() -> Operand = _ op:$("/" / "*" / "%") _ i:_e_e1() {
         Operand::new(op, i, 2)
@@ -321,7 +529,7 @@ peg::parser! { pub grammar clang() for str {
         Binop::union(x, ops)
     }
 
-    rule _e_o4() -> Operand = _ op:$("<" / ">" / "<=" / ">=") _ i:_e_e4() {
+    rule _e_o4() -> Operand = _ op:$("<=" / ">=" / "<" / ">") _ i:_e_e4() {
         Operand::new(op, i, 2)
     }
 
@@ -337,7 +545,7 @@ peg::parser! { pub grammar clang() for str {
         Binop::union(x, ops)
     }
 
-    rule _e_o6() -> Operand = _ op:$("&") _ i:_e_e6() {
+    rule _e_o6() -> Operand = _ op:$("&" !"&") _ i:_e_e6() {
         Operand::new(op, i, 2)
     }
 
@@ -353,7 +561,7 @@ peg::parser! { pub grammar clang() for str {
         Binop::union(x, ops)
     }
 
-    rule _e_o8() -> Operand = _ op:$("|") _ i:_e_e8() {
+    rule _e_o8() -> Operand = _ op:$("|" !"|") _ i:_e_e8() {
         Operand::new(op, i, 2)
     }
 
@@ -377,15 +585,31 @@ peg::parser! { pub grammar clang() for str {
         Binop::union(x, ops)
     }
 
-    rule expr() -> Expr = precedence! {
+    rule __assign_op() -> &'input str = op:$("=" !"=" / "+=" / "-=" / "*=" / "/=" / "%=" / "<<=" / ">>=" / "&=" / "^=" / "|=") { op }
+
+    rule __assign() -> (Expr, &'input str, Expr) = x:_e_e1() _ op:__assign_op() _ y:expr() {
+        (x, op, y)
+    }
+
+    rule expr() -> Expr
+        = x:__assign() { Assign::value(x.0, x.1, x.2) }
+        / x:__expr_cond() { x }
+
+    rule __expr_cond() -> Expr = precedence! {
         cond:(@) _ "?" _ s1:expr() _ ":" _ s2:@ {
             let mut s1 = s1;
             let mut s2 = s2;
 
-            match BuiltinType::dominant(&s1.ty, &s2.ty) {
-                Dominant::Similar => (),
-                Dominant::A => s2.convert(&s1.ty),
-                Dominant::B => s1.convert(&s2.ty)
+            if BuiltinType::is_number(&s1.ty) && BuiltinType::is_number(&s2.ty) {
+                let ty = BuiltinType::usual(&s1.ty, &s2.ty);
+                s1.convert(&ty);
+                s2.convert(&ty)
+            } else {
+                match BuiltinType::dominant(&s1.ty, &s2.ty) {
+                    Dominant::Similar => (),
+                    Dominant::A => s2.convert(&s1.ty),
+                    Dominant::B => s1.convert(&s2.ty)
+                }
             }
             let cond = BuiltinType::convert(&cond.ty, &BuiltinType::Bool.as_id(), &cond.name).deparentify();
             Expr::new(format!("if {} {{\n{tabs}\t{}\n{tabs}}} else {{\n\t{tabs}{}\n\t}}", cond, s1.name, s2.name, tabs = Tab::tabs()), &s1.ty)
@@ -396,68 +620,77 @@ peg::parser! { pub grammar clang() for str {
         }
     }
 
-    rule __stmt_add(attrs: Vec <String>, name: &str, ret: TypeID, arg0: Option <(TypeID, &str)>, args: Vec <Let>) = _ {
-        let mut args = args;
-        let mut attrs = attrs;
-
-        match arg0 {
-            Some(x) => args.insert(0, Let {
-                name: x.1.to_string(),
-                mutable: false,
-                ty: x.0
-            }),
-            None => if args.len() != 0 {
-                panic!("unexpected comma")
-            }
-        }
-
-        if (1..attrs.len()).any(|i| attrs[i..].contains(&attrs[i - 1])) {
-            panic!("cannot have duplicate attributes")
-        }
-
-        let mut flags = FnFlags::empty();
-        flags.insert(FnFlags::SAFE);
-        flags.insert(FnFlags::PUBLIC);
-
-        let mut i = 0;
-        let mut remove = true;
-        while i < attrs.len() {
-            match attrs[i].as_str() {
-                "%U" => flags.remove(FnFlags::SAFE),
-                "%P" => flags.remove(FnFlags::PUBLIC),
-                _ => remove = false
-            }
-
-            if remove {
-                attrs.remove(i);
-                remove = true
-            }
-            i += 1
-        }
+    rule __enumerator_value() -> i128 = name:name() {?
+        Enumerator::find(name).map(|x| x.value).ok_or("integer constant")
+    }
 
-        Function::add(Function {
-            name: name.to_string(),
-            ret,
-            args: args.len(),
-            lets: args,
-            attrs,
-            flags
-        })
+    /// Integer constant expression, evaluated right away
+    rule const_expr() -> i128 = precedence! {
+        cond:@ _ "?" _ x:const_expr() _ ":" _ y:(@) { if cond != 0 { x } else { y } }
+        --
+        x:(@) _ "||" _ y:@ { (x != 0 || y != 0) as i128 }
+        --
+        x:(@) _ "&&" _ y:@ { (x != 0 && y != 0) as i128 }
+        --
+        x:(@) _ "|" _ y:@ { x | y }
+        --
+        x:(@) _ "^" _ y:@ { x ^ y }
+        --
+        x:(@) _ "&" _ y:@ { x & y }
+        --
+        x:(@) _ "==" _ y:@ { (x == y) as i128 }
+        x:(@) _ "!=" _ y:@ { (x != y) as i128 }
+        --
+        x:(@) _ "<=" _ y:@ { (x <= y) as i128 }
+        x:(@) _ ">=" _ y:@ { (x >= y) as i128 }
+        x:(@) _ "<" _ y:@ { (x < y) as i128 }
+        x:(@) _ ">" _ y:@ { (x > y) as i128 }
+        --
+        x:(@) _ "<<" _ y:@ { x << y }
+        x:(@) _ ">>" _ y:@ { x >> y }
+        --
+        x:(@) _ "+" _ y:@ { x + y }
+        x:(@) _ "-" _ y:@ { x - y }
+        --
+        x:(@) _ "*" _ y:@ { x * y }
+        x:(@) _ op:$("/" / "%") _ y:@ { Constant::div(x, y, op) }
+        --
+        "-" _ x:@ { -x }
+        "+" _ x:@ { x }
+        "!" _ x:@ { (x == 0) as i128 }
+        "~" _ x:@ { !x }
+        --
+        "(" _ x:const_expr() _ ")" { x }
+        x:literal() { Constant::parse(&x.name) }
+        x:char_literal() { x as i128 }
+        x:__enumerator_value() { x }
     }
 
-    rule __stmt_arg() -> Let = "," _ ty:ty() _ name:var() _ {
-        Let {
-            name: name.to_string(),
-            ty: ty.mutable(),
-            mutable: false
+    rule __stmt_add(attrs: Vec <String>, name: &str, ret: TypeID, params: Vec <Let>) = _ pos:position!() {
+        if params.iter().any(|x| x.name.is_empty()) {
+            panic!("parameter name omitted in definition of `{}`", name)
         }
+        Function::define(pos, Function::new(name, ret, params, attrs))
     }
 
+    /// Parameters of a function; `(void)` means there are none
+    rule __fn_params() -> Vec <Let>
+        = "(" _ "void" _ ")" { Vec::new() }
+        / "(" _ params:__fn_param() ** ("," _) ("," _)? ")" { params }
+
+    /// Parameter, which name may be omitted in prototypes
+    rule __fn_param() -> Let
+        = x:__param() { Let::new(x.1, x.0) }
+        / ty:ty() _ dims:__dims() { Let::new("", adjust_param(ty, dims)) }
+
     rule __stmt_fn_attr_inside() -> String
         = "\"noreturn\""                     { String::from("%N") }
         / "\"safe\""                         { String::from("%S") }
         / "\"unsafe\""                       { String::from("%U") }
-        / "rust" _ "(" _ attr:string() _ ")" { attr }
+        / "\"overflow(wrap)\""               { String::from("%W") }
+        / "\"overflow(trap)\""               { String::from("%T") }
+        / "\"overflow(checked)\""            { String::from("%C") }
+        / "rust" _ "(" _ attr:raw_string() _ ")" { attr }
 
     rule __stmt_fn_attr() -> String
         = "inline" _ { String::from("inline") }
@@ -466,32 +699,247 @@ peg::parser! { pub grammar clang() for str {
 
     rule __stmt_fn_attrs() -> Vec <String> = attrs:__stmt_fn_attr()*
 
-    rule __stmt_fn_first_arg() -> (TypeID, &'input str) = ty:ty() _ arg:var() _ {
-        (ty, arg)
+    /// Header of a function defined later in the file, see `Function::find`
+    pub rule prototype() -> Function = _ attrs:__stmt_fn_attrs() ret:ty() _ name:var() _ params:__fn_params() _ {
+        Function::new(name, ret, params, attrs)
     }
 
     rule __stmt_return_is_last() -> () = &"}"
 
     rule __stmt_return_possible_no() -> Expr = __ e:expr() { e }
 
-    rule stmt() -> String = precedence! {
+    rule __in_body() = "" {?
+        if Function::is_in_body() {
+            Ok(())
+        } else {
+            Err("function body")
+        }
+    }
+
+    rule __init() -> Init
+        = "{" _ items:__init() ** ("," _) ("," _)? "}" _ { Init::List(items) }
+        / e:expr() _ { Init::Expr(e) }
+
+    rule __decl_init() -> Init = "=" _ i:__init() { i }
+
+    /// Array dimensions after a name; the first one may be left to the initializer
+    rule __dims() -> Vec <Option <usize>> = dims:("[" _ x:const_expr()? _ "]" _ {
+        x.map(|x| if x < 0 {
+            panic!("size of array is negative")
+        } else {
+            x as usize
+        })
+    })* {?
+        if dims.iter().skip(1).any(Option::is_none) {
+            Err("array size")
+        } else {
+            Ok(dims)
+        }
+    }
+
+    rule __decl(base: &TypeID) -> String = ptr:__ty_ptr()* _ name:var() _ dims:__dims() init:__decl_init()? {
+        let mut dims = dims;
+        if let Some(None) = dims.first() {
+            dims[0] = Some(init.as_ref().expect("array size is missing").array_len())
+        }
+        let ty = base.with_ptr(ptr).with_dims(dims.into_iter().map(Option::unwrap).collect());
+        Function::get().declare(name, ty, init)
+    }
+
+    rule __at_file_scope() = "" {?
+        if Function::is_in_body() {
+            Err("file scope")
+        } else {
+            Ok(())
+        }
+    }
+
+    rule __storage() -> &'input str = x:$("static" / "extern") __ { x }
+
+    rule __constant_begin() = "" { *Global::is_constant() = true }
+
+    rule __constant_end() = "" { *Global::is_constant() = false }
+
+    /// Initializer of a static variable, which has to be constant
+    rule __static_init() -> Option <Init> = __constant_begin() init:__decl_init()? __constant_end() { init }
+
+    rule __global_decl(base: &TypeID) -> (usize, &'input str, TypeID, Option <Init>)
+        = ptr:__ty_ptr()* _ pos:position!() name:var() _ dims:__dims() init:__static_init() {
+        let mut dims = dims;
+        if let Some(None) = dims.first() {
+            dims[0] = Some(init.as_ref().expect("array size is missing").array_len())
+        }
+        (pos, name, base.with_ptr(ptr).with_dims(dims.into_iter().map(Option::unwrap).collect()), init)
+    }
+
+    /// Parameter, where array is adjusted to pointer
+    rule __param() -> (TypeID, &'input str) = ty:ty() _ name:var() _ dims:__dims() {
+        (adjust_param(ty, dims), name)
+    }
+
+    rule __scope_enter() = "" {
+        Function::get().enter();
+        *Tab::number_tabs() += 1
+    }
+
+    rule block() -> String = "{" __scope_enter() body:clang() _ "}" _ {
+        close_scope(body)
+    }
+
+    /// Body of `if`, loops etc.: either a block or a single statement
+    rule substmt() -> String
+        = b:block() { b }
+        / __scope_enter() s:stmt() { close_scope(s) }
+
+    rule __cond() -> String = "(" _ cond:expr() _ ")" _ {
+        BuiltinType::convert(&cond.ty, &BuiltinType::Bool.as_id(), &cond.name).deparentify()
+    }
+
+    rule __if() -> String = "if" _ cond:__cond() then:substmt() other:__else()? {
+        format!("if {} {}{}", cond, then, other.unwrap_or_default())
+    }
+
+    rule __else() -> String
+        = "else" __ i:__if() { format!(" else {}", i) }
+        / "else" __kw_end() _ s:substmt() { format!(" else {}", s) }
+
+    rule __kw_end() = !(letter() / digit10())
+
+    rule __while() -> String = "while" _ cond:__cond() body:substmt() {
+        let label = Jump::label();
+        let body = Loop::jumps(body, label, format!("continue 'l{};", label));
+        format!("{}while {} {}", Loop::label(&body, label), cond, body)
+    }
+
+    rule __do_while() -> String = "do" __kw_end() _ body:substmt() "while" _ cond:__cond() ";" _ {
+        let label = Jump::label();
+        let body = Loop::jumps(body, label, format!("break 'c{};", label));
+        format!("'l{label}: loop {{\n{tabs}\t{}\n{tabs}\tif !({}) {{\n{tabs}\t\tbreak 'l{label};\n{tabs}\t}}\n{tabs}}}",
+            Tab::indent(&Loop::continuable(body, label)), cond, label = label, tabs = Tab::tabs())
+    }
+
+    rule __case_label() -> Option <i128>
+        = "case" __kw_end() _ value:const_expr() _ ":" _ { Some(value) }
+        / "default" _ ":" _ { None }
+
+    rule __case_group() -> (Vec <Option <i128>>, Vec <String>) = labels:__case_label()+ stmts:stmt()* {
+        (labels, stmts)
+    }
+
+    rule __switch() -> String = "switch" _ "(" _ value:expr() _ ")" _ "{" __scope_enter() _ groups:__case_group()* "}" _ {
+        let label = Jump::label();
+        let code = Switch::lower(value, groups, label);
+        format!("{}{}", Switch::label(&code, label), close_scope(code))
+    }
+
+    /// Initial clause of `for`; when it declares exactly one integer,
+    /// the name and value of it are returned too
+    rule __for_init() -> (String, Option <(&'input str, TypeID, String)>)
+        = base:__ty_base() _ name:var() _ "=" _ e:expr() _ ";" _ {
+            let value = BuiltinType::convert(&e.ty, &base, &e.name).deparentify();
+            let counter = if BuiltinType::is_integer(&base) && base.ptr.is_empty() {
+                Some((name, base.clone(), value))
+            } else {
+                None
+            };
+            (Function::get().declare(name, base, Some(Init::Expr(e))), counter)
+        }
+        / base:__ty_base() _ decls:__decl((&base)) ++ ("," _) ";" _ { (decls.join(" "), None) }
+        / e:expr() _ ";" _ { (format!("{};", e.name), None) }
+        / ";" _ { (String::new(), None) }
+
+    /// Condition of `for`; `i < n` and `i <= n` also return the name, operator and bound
+    rule __for_cond() -> (Option <String>, Option <(&'input str, &'input str, Expr)>)
+        = name:var() _ op:$("<=" / "<") _ bound:_e_e4() _ &";" {
+            let bound_copy = Expr::new(bound.name.clone(), &bound.ty);
+            let cond = Binop::union(Operand::parse_to_var(name), vec![Operand::new(op, bound, 2)]);
+            let cond = BuiltinType::convert(&cond.ty, &BuiltinType::Bool.as_id(), &cond.name).deparentify();
+            (Some(cond), Some((name, op, bound_copy)))
+        }
+        / cond:expr() {
+            (Some(BuiltinType::convert(&cond.ty, &BuiltinType::Bool.as_id(), &cond.name).deparentify()), None)
+        }
+        / "" { (None, None) }
+
+    /// Step of `for`; a plain increment of a variable returns its name instead
+    rule __for_step() -> Result <&'input str, Option <Expr>>
+        = name:("++" _ name:var() _ &")" { name } / name:var() _ "++" _ &")" { name }) { Ok(name) }
+        / step:expr()? _ { Err(step) }
+
+    rule __for() -> String = "for" _ "(" _ __scope_enter() init:__for_init() cond:__for_cond() _ ";" _ step:__for_step() ")" _ body:substmt() {
+        let label = Jump::label();
+        let step = match step {
+            Ok(name) => {
+                // range loop is possible only if nobody else changes the counter
+                let is_range = match (&init.1, &cond.1) {
+                    (Some((counter, ty, _)), Some((cond, op, bound))) => *counter == name && *cond == name
+                        && !Function::get().find_let(name).unwrap().mutable
+                        && Loop::is_invariant(&bound.name)
+                        && Loop::is_range_bound(ty, op, bound),
+                    _ => false
+                };
+                if is_range {
+                    let (_, ty, from) = init.1.unwrap();
+                    let (_, op, bound) = cond.1.unwrap();
+                    let to = BuiltinType::convert(&bound.ty, &ty, &bound.name).deparentify();
+                    let body = Loop::jumps(body, label, format!("continue 'l{};", label));
+                    let range = format!("{}for {} in {}{}{} {}", Loop::label(&body, label), Function::get().find_let(name).unwrap().real, from,
+                        if op == "<" { ".." } else { "..=" }, to, Tab::dedent(&body));
+                    close_scope(String::new());
+                    return range
+                }
+                Unop::union(Operand::parse_to_var(name), "++b").name
+            },
+            Err(step) => step.map(|x| x.name).unwrap_or_default()
+        };
+
+        let body = Loop::jumps(body, label, format!("break 'c{};", label));
+        let tabs = Tab::tabs();
+        close_scope(format!("{}{}'l{label}: loop {{\n{tabs}\t{}{}{}\n{tabs}}}", init.0, if init.0.is_empty() {
+            String::new()
+        } else {
+            Tab::tabs_nl()
+        }, match cond.0 {
+            Some(cond) => format!("if !({}) {{\n{tabs}\t\tbreak 'l{label};\n{tabs}\t}}\n{tabs}\t", cond, label = label, tabs = tabs),
+            None => String::new()
+        }, Tab::indent(&Loop::continuable(body, label)), if step.is_empty() {
+            String::new()
+        } else {
+            format!("\n{}\t{};", tabs, step)
+        }, label = label, tabs = tabs))
+    }
+
+    rule __line() = pos:position!() {
+        Overflow::at(pos)
+    }
+
+    rule stmt() -> String = __line() s:__stmt() {
+        Record::flush(s)
+    }
+
+    rule __stmt() -> String = precedence! {
         "return" e:__stmt_return_possible_no()? _ ";" _ is_last:__stmt_return_is_last()? _ {
             if e.is_none() {
                 if Function::get().ret != BuiltinType::Void.as_id() {
                     panic!("expected value")
                 }
-                return String::new()
+                return if is_last.is_some() && *Tab::number_tabs() == 0 {
+                    String::new()
+                } else {
+                    String::from("return;")
+                }
             }
             let e = e.unwrap();
             let result = BuiltinType::convert(&e.ty, &Function::get().ret, &e.name).deparentify();
-            if is_last.is_some() {
+            if is_last.is_some() && *Tab::number_tabs() == 0 {
                 format!("{}", result)
             } else {
                 format!("return {};", result)
             }
         }
 
-        "typedef" __ ty:ty() _ new:var() _ ";" _ {
+        "typedef" __ ty:ty() _ new:var() _ dims:__dims() ";" _ {
+            let ty = ty.with_dims(dims.into_iter().map(|x| x.expect("array size is missing")).collect());
             let real = FullType::real(&ty);
             let x = if new != real && !new.is_keyword() {
                 format!("type {} = {};", new, real)
@@ -502,7 +950,108 @@ peg::parser! { pub grammar clang() for str {
             x
         }
 
-        attrs:__stmt_fn_attrs() ret:ty() _ name:var() _ "(" _ arg0:__stmt_fn_first_arg()? args:__stmt_arg()* ("," _)? ")" __stmt_add(attrs, name, ret, arg0, args) "{" _ body:clang()? _ "}" _ {
+        __record_decl() {
+            String::new()
+        }
+
+        __in_body() base:__ty_base() _ decls:__decl((&base)) ++ ("," _) ";" _ {
+            decls.join(" ")
+        }
+
+        __in_body() "static" __ base:__ty_base() _ decls:__global_decl((&base)) ++ ("," _) ";" _ {
+            for (pos, name, ty, init) in decls {
+                let real = Global::declare_in_function(pos, name, ty.clone(), init);
+                Function::get().declare_static(name, ty, real)
+            }
+            String::new()
+        }
+
+        __at_file_scope() storage:__storage()? base:__ty_base() _ decls:__global_decl((&base)) ++ ("," _) ";" _ {
+            for (pos, name, ty, init) in decls {
+                Global::declare(pos, storage, name, ty, init)
+            }
+            String::new()
+        }
+
+        __in_body() b:block() {
+            b
+        }
+
+        __in_body() i:__if() {
+            i
+        }
+
+        __in_body() l:__while() {
+            l
+        }
+
+        __in_body() l:__do_while() {
+            l
+        }
+
+        __in_body() l:__for() {
+            l
+        }
+
+        __in_body() s:__switch() {
+            s
+        }
+
+        __in_body() "break" _ ";" _ {
+            String::from(Jump::BREAK)
+        }
+
+        __in_body() "continue" _ ";" _ {
+            String::from(Jump::CONTINUE)
+        }
+
+        __in_body() ";" _ {
+            String::new()
+        }
+
+        __in_body() "goto" __ name:name() _ ";" _ {
+            Jump::goto(name)
+        }
+
+        __in_body() name:var() _ ":" _ s:stmt() {
+            format!("{}{}", Jump::target(name), s)
+        }
+
+        __in_body() x:__assign() _ ";" _ {
+            Assign::stmt(x.0, x.1, x.2)
+        }
+
+        __in_body() e:expr() _ ";" _ {
+            if e.void_cast {
+                let block = e.name.deparentify();
+                block[2..block.len() - 2].to_string()
+            } else {
+                format!("{};", e.name)
+            }
+        }
+
+        attrs:__stmt_fn_attrs() ret:ty() _ name:var() _ "(" _ ")" _ ";" _ {
+            let mut f = Function::new(name, ret, Vec::new(), attrs);
+            f.flags.insert(FnFlags::UNPROTOTYPED);
+            Function::prototype(f);
+            String::new()
+        }
+
+        attrs:__stmt_fn_attrs() ret:ty() _ name:var() _ params:__fn_params() _ ";" _ {
+            Function::prototype(Function::new(name, ret, params, attrs));
+            String::new()
+        }
+
+        attrs:__stmt_fn_attrs() ret:ty() _ name:var() _ params:__fn_params() __stmt_add(attrs, name, ret, params) "{" _ body:clang()? _ "}" _ {
+            let body = body.unwrap_or_else(|| if Function::get().ret == BuiltinType::Void.as_id() {
+                String::new()
+            } else {
+                panic!("it's not allowed to have empty body for functions, which return type is non-void")
+            });
+            Jump::check_resolved(&body);
+            let body = Function::get().resolve_unsafe(body);
+            let body = Function::get().resolve_lets(body);
+
             let x = format!("\n{}{}{}fn {}({}){} {{\n\t{}\n}}\n\n",
                 {
                     let mut s = String::new();
@@ -514,7 +1063,7 @@ peg::parser! { pub grammar clang() for str {
                     s
                 },
                 if Function::get().flags.contains(FnFlags::PUBLIC) { "pub " } else { "" },
-                if Function::get().flags.contains(FnFlags::SAFE) { "" } else { "unsafe " },
+                Function::get().header(),
                 Function::get().name,
                 {
                     let mut s = String::new();
@@ -523,7 +1072,7 @@ peg::parser! { pub grammar clang() for str {
                             "mut "
                         } else {
                             ""
-                        }, arg.name, FullType::real(&arg.ty)).as_str())
+                        }, arg.real, FullType::real(&arg.ty)).as_str())
                     }
                     if !s.is_empty() {
                         s.pop(); // erase ' '
@@ -540,54 +1089,143 @@ peg::parser! { pub grammar clang() for str {
                         format!(" -> {}", FullType::real(&Function::get().ret))
                     }
                 },
-                body.unwrap_or_else(|| if Function::get().ret == BuiltinType::Void.as_id() {
-                    String::new()
-                } else {
-                    panic!("it's not allowed to have empty body for functions, which return type is non-void")
-                })
+                body
             );
 
             // Clear everything that is connected to current function
 
             AliasType::clear();
+            Enumerator::clear();
+            Function::get().flags.remove(FnFlags::BODY);
 
             x
         }
     }
 
-    pub rule clang() -> String = _ stmts:stmt()* { stmts.join(&Tab::tabs_nl()) }
+    pub rule clang() -> String = _ stmts:stmt()* { Goto::lower(stmts).join(&Tab::tabs_nl()) }
 
     ///////////////////////////////////////////////////////////////////////////////////////////////////
     //                                     PREPROCESSOR                                              //
     ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-    // rule __preprocessor_expr_one() -> String = precedence! {
-    //     "defined" _ "(" _ name:name() _ ")" {
-    //         Macro::is_defined(name).to_string()
-    //     }
-    //
-    //     "defined" __ name:name() {
-    //         Macro::is_defined(name).to_string()
-    //     }
-    //
-    //     name:name() {
-    //         Macro::find(name).unwrap_or(String::new())
-    //     }
-    //
-    //     any:any_except_of_newline() {
-    //         any.to_string()
-    //     }
-    // }
+    /// Expression of `#if` after `defined` and macros are replaced
+    pub rule condition() -> MaxIntResult = ___ x:__condition() ___ { x }
+
+    rule __condition() -> MaxIntResult
+        = c:__condition_binop() ___ "?" ___ x:__condition() ___ ":" ___ y:__condition() { MaxInt::ternary(c, x, y) }
+        / __condition_binop()
+
+    rule __condition_binop() -> MaxIntResult = precedence! {
+        x:(@) ___ "||" ___ y:@ { MaxInt::binop(x, "||", y) }
+        --
+        x:(@) ___ "&&" ___ y:@ { MaxInt::binop(x, "&&", y) }
+        --
+        x:(@) ___ "|" ___ y:@ { MaxInt::binop(x, "|", y) }
+        --
+        x:(@) ___ "^" ___ y:@ { MaxInt::binop(x, "^", y) }
+        --
+        x:(@) ___ "&" ___ y:@ { MaxInt::binop(x, "&", y) }
+        --
+        x:(@) ___ op:$("==" / "!=") ___ y:@ { MaxInt::binop(x, op, y) }
+        --
+        x:(@) ___ op:$("<=" / ">=" / "<" / ">") ___ y:@ { MaxInt::binop(x, op, y) }
+        --
+        x:(@) ___ op:$("<<" / ">>") ___ y:@ { MaxInt::binop(x, op, y) }
+        --
+        x:(@) ___ op:$(['+' | '-']) ___ y:@ { MaxInt::binop(x, op, y) }
+        --
+        x:(@) ___ op:$(['*' | '/' | '%']) ___ y:@ { MaxInt::binop(x, op, y) }
+        --
+        op:$(['-' | '+' | '!' | '~']) ___ x:@ { MaxInt::unop(op, x) }
+        --
+        "(" ___ x:__condition() ___ ")" { x }
+        float() !(letter() / digit10()) { Err(String::from("floating constant")) }
+        x:num() suffix:__int_suffix()? !(letter() / digit10()) { MaxInt::literal(&x, suffix.map_or(false, |x| x.0)) }
+        x:char_literal() { MaxInt::new(x as i32 as i64, false) }
+        // names left after expansion
+        name() { MaxInt::new(0, false) }
+    }
 
     rule any_except_of_newline() -> &'input str = s:$([^ '#' | '\n']) { s }
 
-    rule if_stmt() -> bool
-        = "ifdef" ____() name:name() { Macro::is_defined(name) }
-        / "ifndef" ____() name:name() { !Macro::is_defined(name) }
+    /// String or character literal, where nothing is replaced
+    rule __quoted() -> &'input str
+        = x:$("L"? "\"" ("\\" [_] / [^ '\"' | '\\' | '\n'])* "\"") { x }
+        / x:$("L"? "'" ("\\" [_] / [^ '\'' | '\\' | '\n'])* "'") { x }
+
+    /// Parameters of function-like macro and whether it ends with `...`
+    rule __macro_params() -> (Vec <&'input str>, bool)
+        = "..." { (Vec::new(), true) }
+        / params:(name() ++ (___ "," ___)) variadic:(___ "," ___ "...")? { (params, variadic.is_some()) }
+        / "" { (Vec::new(), false) }
+
+    /// Arguments of macro invocation with nested parentheses
+    rule __macro_parens() = "(" (__quoted() / __macro_parens() / [^ '(' | ')' | '"' | '\''])* ")"
+
+    /// Arguments following `text`, which are rescanned together with its expansion
+    rule __macro_args(text: String) -> String
+        = __when((Macro::pending(&text) == Some(0))) x:$([' ' | '\t' | '\n']* __macro_parens()) rest:__macro_args((format!("{}{}", text, x.replace('\n', " ")))) {
+            format!("{}{}", x, rest)
+        }
+        / __when((Macro::pending(&text).map_or(false, |x| x > 0))) x:$((__quoted() / __macro_parens() / [^ '(' | ')' | '"' | '\''])* ")") rest:__macro_args((format!("{}{}", text, x.replace('\n', " ")))) {
+            format!("{}{}", x, rest)
+        }
+        / "" { String::new() }
+
+    rule __line_text() -> &'input str = x:$([^ '\n']*) { x }
+
+    rule __when(x: bool) = "" {?
+        if x {
+            Ok(())
+        } else {
+            Err("group of conditional")
+        }
+    }
+
+    rule __if_head() -> bool
+        = "ifdef" ____() name:name() ___ { Macro::is_defined(name) }
+        / "ifndef" ____() name:name() ___ { !Macro::is_defined(name) }
+        / "if" !(letter() / digit10()) x:__line_text() { Macro::condition(x) }
+
+    rule __endif() = ___ "#" ___ "endif" ___ (newline() / ![_])
+
+    rule __directive_end() = ___ "#" ___ ("elif" / "else" / "endif") !(letter() / digit10())
+
+    rule __skipped_line()
+        = !__directive_end() ___ "#" ___ "if" [^ '\n']* "\n" __skipped_nested()
+        / !__directive_end() [^ '\n']* "\n"
+
+    /// Nested conditional inside of skipped group up to its `#endif`
+    rule __skipped_nested()
+        = __skipped_line()* (___ "#" ___ ("elif" / "else") [^ '\n']* "\n" __skipped_line()*)* ___ "#" ___ "endif" [^ '\n']* ("\n" / ![_])
 
-    rule else_stmt() -> String = "#" ___ "else" ___ newline() stmts:preprocess() { stmts }
+    /// Group of conditional, which is not taken, so that nothing in it is processed
+    rule __skipped() -> String = lines:$(__skipped_line()*) {
+        let n = lines.matches('\n').count();
+        unsafe { LINE += n }
+        "\n".repeat(n)
+    }
+
+    /// Group of conditional, which is taken
+    rule __group() -> String = stmts:preprocessor_stmt()* { stmts.join("") }
+
+    /// Rest of conditional after the condition `cond` of the current group
+    rule __if_rest(cond: bool) -> String
+        = __when(cond) body:__group() rest:__if_skip_rest() { body + &rest }
+        / __when((!cond)) skipped:__skipped() rest:__if_next() { skipped + &rest }
+
+    rule __if_next() -> String
+        = ___ "#" ___ "elif" !(letter() / digit10()) x:__line_text() newline() rest:__if_rest((Macro::condition(x))) { format!("\n{}", rest) }
+        / ___ "#" ___ "else" ___ newline() body:__group() __endif() { format!("\n{}\n", body) }
+        / __endif() { String::from("\n") }
 
-    rule line_possible_file() -> String = ____() file:string() { file }
+    /// `#elif` and `#else` groups after the taken one
+    rule __if_skip_rest() -> String
+        = groups:(___ "#" ___ ("elif" / "else") [^ '\n']* newline() x:__skipped() { format!("\n{}", x) })* __endif() {
+            groups.join("") + "\n"
+        }
+
+    rule line_possible_file() -> String = ____() file:raw_string() { file }
 
     rule preprocessor_stmt() -> String = precedence! {
         "#" ___ "define" ____() name:name() ___ newline() {
@@ -595,13 +1233,32 @@ peg::parser! { pub grammar clang() for str {
             String::from("\n")
         }
 
-        "#" ___ "define" ____() name:name() __ value:any_except_of_newline()+ newline() {
-            Macro::add(name.to_string(), value.join(""));
+        "#" ___ "define" ____() name:name() "(" ___ params:__macro_params() ___ ")" ___ value:__line_text() newline() {
+            Macro::add_function(name, params.0, params.1, value.trim_end());
+            String::from("\n")
+        }
+
+        "#" ___ "define" ____() name:name() ____() value:__line_text() newline() {
+            Macro::add(name.to_string(), value.trim_end());
             String::from("\n")
         }
 
-        "#" ___ "include" ___ path:string() ___ newline() {
-            format!("\n{}", preprocess_file(crate::read_file(&path)))
+        "#" ___ "include" ___ path:raw_string() ___ newline() {
+            format!("\n{}", Include::file(&path, false))
+        }
+
+        "#" ___ "include" ___ "<" path:$([^ '>' | '\n']+) ">" ___ newline() {
+            format!("\n{}", Include::file(path, true))
+        }
+
+        "#" ___ "pragma" ____() "once" ___ newline() {
+            Include::pragma_once();
+            String::from("\n")
+        }
+
+        // other pragmas are ignored
+        "#" ___ "pragma" !(letter() / digit10()) __line_text() newline() {
+            String::from("\n")
         }
 
         "#" ___ "undef" ____() name:name() newline() {
@@ -612,32 +1269,20 @@ peg::parser! { pub grammar clang() for str {
             String::from("\n")
         }
 
-        "#" ___ cond:if_stmt() ___ newline() stmts:preprocess() other:else_stmt()? "#" ___ "endif" ___ newline() {
-            if cond {
-                format!("\n{}\n", stmts)
-            } else {
-                // for elif in elifs {
-                //     if elif.0 {
-                //         return format!("\n{}\n", elif.1)
-                //     }
-                // }
-                match other {
-                    Some(stmts) => format!("\n\n{}\n", stmts),
-                    None => "\n".repeat(stmts.chars().fold(2, |accum, x| accum + if x == '\n' { 1 } else { 0 }))
-                }
-            }
+        "#" ___ cond:__if_head() newline() rest:__if_rest(cond) {
+            format!("\n{}", rest)
         }
 
         "#" ___ "line" ____() line:num() file:line_possible_file()? ___ newline() {
             assert!(line.find('.').is_none(), "line cannot be float");
             unsafe {
                 LINE = line.parse().expect("wrong format");
-                match file {
-                    Some(file) => FILE = format!("\"{}\"", file),
-                    None => ()
+                if let Some(file) = file {
+                    FILE = format!("{:?}", file);
+                    *Include::name() = file
                 }
+                format!("\n{}", Include::marker(LINE))
             }
-            String::from("\n")
         }
 
         "#" ___ newline() {
@@ -652,11 +1297,15 @@ peg::parser! { pub grammar clang() for str {
             unsafe { FILE.clone() }
         }
 
-        name:name() {
-            match Macro::find(name) {
-                Some(x) => x,
-                None => name.to_string()
-            }
+        x:__quoted() {
+            x.to_string()
+        }
+
+        name:name() args:__macro_args((name.to_string())) {
+            // the arguments may take several lines, which are kept after the expansion
+            let lines = args.matches('\n').count();
+            unsafe { LINE += lines }
+            Macro::expand(&format!("{}{}", name, args.replace('\n', " "))) + &"\n".repeat(lines)
         }
 
         newline() {
@@ -671,6 +1320,23 @@ peg::parser! { pub grammar clang() for str {
     pub rule preprocess() -> String = _ stmts:preprocessor_stmt()* { stmts.join("") }
 } }
 
+/// Parameter declared as array is adjusted to pointer
+fn adjust_param(ty: TypeID, dims: Vec <Option <usize>>) -> TypeID {
+    match dims.len() {
+        0 => ty,
+        1 => ty.with_ptr(vec![true]),
+        _ => panic!("pointers to arrays are not supported")
+    }
+}
+
+/// Closes the scope opened by `__scope_enter`, wrapping `body` into braces
+fn close_scope(body: String) -> String {
+    let inner = Tab::tabs();
+    Function::get().leave();
+    *Tab::number_tabs() -= 1;
+    format!("{{\n{}{}\n{}}}", inner, body, Tab::tabs())
+}
+
 fn preprocess_file(mut code: String) -> String {
     Comment::uncomment(&mut code, &[
         Comment {
@@ -688,16 +1354,26 @@ fn preprocess_file(mut code: String) -> String {
     clang::preprocess(&code).unwrap()
 }
 
-pub fn start(file: &str, code: String) -> String {
+pub fn start(file: &str, include_dirs: Vec <String>, code: String) -> String {
+    // nothing is shared with the files translated before
+    FullType::types().clear();
+    AliasType::types().clear();
+    Record::reset();
+    Enumerator::reset();
+    Function::reset();
+
     BuiltinType::add_all();
     BuiltinFunction::add_all();
     Op::add_all();
 
     Macro::predefine_all(file);
+    Include::begin(file, include_dirs);
     let code = preprocess_file(code);
+    let code = Overflow::begin(file, code);
+    Function::scan(&code);
 
     match clang::clang(&code) {
-        Ok(s) => s,
+        Ok(s) => Function::resolve_calls(Global::lower_all() + &Function::lower_externs() + &s),
         Err(e) => panic!("{}", e)
     }
 }
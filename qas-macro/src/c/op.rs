@@ -1,4 +1,5 @@
-use super::{Expr, BuiltinType, Function, TypeID, Dominant, FnFlags, BitPtrIter};
+use crate::StringExt;
+use super::{Expr, BuiltinType, Function, TypeID, Dominant, FnFlags, FullType, Enumerator, Record, Constant, Global};
 
 pub struct Op {
     pub name: String,
@@ -11,7 +12,6 @@ pub struct Op {
 impl Op {
     pub fn add_all() {
         let int = BuiltinType::SignedInt.as_id();
-        let uint = BuiltinType::UnsignedInt.as_id();
         let r#bool = BuiltinType::Bool.as_id();
         let same = BuiltinType::Count.as_id();
 
@@ -23,7 +23,13 @@ impl Op {
             if !x.mutable {
                 panic!("cannot apply operator to const value")
             }
-            BuiltinType::is_integer(x)
+            BuiltinType::is_integer(x) || BuiltinType::is_pointer(x)
+        }
+        fn is_mutable(x: &TypeID) -> bool {
+            if !x.mutable {
+                panic!("cannot assign to const value")
+            }
+            true
         }
 
         /* binary */
@@ -35,8 +41,8 @@ impl Op {
         Self::add("+", 2, BuiltinType::is_arithmetic, int.clone(), Clone::clone);
         Self::add("-", 2, BuiltinType::is_arithmetic, int.clone(), Clone::clone);
 
-        Self::add("<<", 2, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
-        Self::add(">>", 2, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
+        Self::add("<<", 2, BuiltinType::is_integer, int.clone(), Clone::clone);
+        Self::add(">>", 2, BuiltinType::is_integer, int.clone(), Clone::clone);
 
         Self::add(">", 2, BuiltinType::is_builtin, same.clone(), always_bool);
         Self::add("<", 2, BuiltinType::is_builtin, same.clone(), always_bool);
@@ -46,16 +52,22 @@ impl Op {
         Self::add("==", 2, BuiltinType::is_builtin, same.clone(), always_bool);
         Self::add("!=", 2, BuiltinType::is_builtin, same.clone(), always_bool);
 
-        Self::add("&", 2, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
+        Self::add("&", 2, BuiltinType::is_integer, int.clone(), Clone::clone);
 
-        Self::add("^", 2, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
+        Self::add("^", 2, BuiltinType::is_integer, int.clone(), Clone::clone);
 
-        Self::add("|", 2, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
+        Self::add("|", 2, BuiltinType::is_integer, int.clone(), Clone::clone);
 
         Self::add("&&", 2, is_bool, r#bool.clone(), Clone::clone);
 
         Self::add("||", 2, is_bool, r#bool.clone(), Clone::clone);
 
+        /* assignment */
+
+        for op in &["=", "+=", "-=", "*=", "/=", "%=", "<<=", ">>=", "&=", "^=", "|="] {
+            Self::add(op, 2, is_mutable, same.clone(), Clone::clone);
+        }
+
         /* unary */
 
         // ++ postfix(a = after)
@@ -69,11 +81,7 @@ impl Op {
         Self::add("--b", 1, is_mutable_integer, same.clone(), Clone::clone);
         Self::add("+", 1, BuiltinType::is_arithmetic, int.clone(), Clone::clone);
         Self::add("-", 1, BuiltinType::is_arithmetic, int.clone(), Clone::clone);
-        Self::add("&", 1, is_any, same.clone(), |x| TypeID {
-            idx: x.idx,
-            ptr: BitPtrIter::append(x.ptr.clone(), x.mutable),
-            mutable: true
-        });
+        Self::add("&", 1, is_any, same.clone(), |x| x.with_ptr(vec![true]));
         Self::add("*", 1, |x| {
             if x.idx == 0 {
                 panic!("cannot deref non-ptr")
@@ -87,11 +95,12 @@ impl Op {
             TypeID {
                 idx: x.idx,
                 ptr: x.ptr,
-                mutable
+                mutable,
+                dims: Vec::new()
             }
         });
         Self::add("!", 1, is_bool, r#bool.clone(), Clone::clone);
-        Self::add("~", 1, BuiltinType::is_unsigned, uint.clone(), Clone::clone);
+        Self::add("~", 1, BuiltinType::is_integer, int.clone(), Clone::clone);
     }
 
     pub fn add(name: &str, operands: u8, matching: fn(&TypeID) -> bool, default: TypeID, result: fn(&TypeID) -> TypeID) {
@@ -137,18 +146,57 @@ impl Operand {
         &Op::ops()[self.op]
     }
 
-    pub fn parse_to_num(i: String) -> Expr {
-        let has_dot = i.chars().find(|x| *x == '.').is_some();
+    /// Literal typed by the first type of its list, which can represent it
+    pub fn parse_to_num(i: String, suffix: Option <(bool, bool)>) -> Expr {
+        use BuiltinType::*;
+
+        let value = Constant::parse(&i);
+        let is_decimal = !i.starts_with("0b") && !i.starts_with("0o") && !i.starts_with("0x");
+        let types: &[BuiltinType] = match suffix.unwrap_or_default() {
+            (false, false) if is_decimal => &[SignedInt, SignedLong],
+            (false, false) => &[SignedInt, UnsignedInt, SignedLong, UnsignedLong],
+            (true, false) => &[UnsignedInt, UnsignedLong],
+            (false, true) if is_decimal => &[SignedLong],
+            (false, true) => &[SignedLong, UnsignedLong],
+            (true, true) => &[UnsignedLong]
+        };
+
+        match types.iter().map(BuiltinType::as_id).find(|ty| {
+            let (min, max) = BuiltinType::range(ty);
+            min <= value && value <= max
+        }) {
+            Some(ty) => Expr::new(Constant::typed(i, value, &ty), &ty),
+            None => panic!("integer literal `{}` is too large", i)
+        }
+    }
 
-        Expr::new(i, &if has_dot {
-            BuiltinType::Float.as_id()
-        } else {
-            BuiltinType::UnsignedChar.as_id()
-        })
+    /// Floating literal, which is `double` unless it has a suffix
+    pub fn parse_to_float(i: String, suffix: Option <&str>) -> Expr {
+        Expr::new(i, &match suffix {
+            Some("f" | "F") => BuiltinType::Float,
+            _ => BuiltinType::Double
+        }.as_id())
     }
 
     pub fn parse_to_var(i: &str) -> Expr {
-        Expr::new(i.to_string(), &Function::get().type_of_let(&i).expect("unknown variable"))
+        if Function::is_in_body() {
+            if let Some(x) = Function::get().find_let(&i) {
+                if *Global::is_constant() && !Global::globals().iter().any(|y| y.is_in_function && y.name == x.real) {
+                    panic!("initializer element is not constant")
+                }
+                let mut x = Expr::lvalue(String::new(), x.real.clone(), &x.ty);
+                // `static` of the function is read like any other
+                x.name = Assign::read(&x);
+                return x
+            }
+        }
+        match Enumerator::find(i) {
+            Some(_) => Expr::new(i.to_string(), &BuiltinType::SignedInt.as_id()),
+            None => match Global::find(i) {
+                Some(x) => x.to_expr(),
+                None => panic!("unknown variable `{}`", i)
+            }
+        }
     }
 }
 
@@ -172,9 +220,55 @@ impl Binop {
         if ops.is_empty() { return x }
 
         for mut op in ops {
+            let name = op.op().name.as_str();
+            if (name == "+" || name == "-") && (BuiltinType::is_pointer(&x.ty) || BuiltinType::is_pointer(&op.expr.ty)) {
+                x = Self::pointer(x, name, op.expr);
+                continue
+            } else if name == "==" || name == "!=" {
+                // comparison with null pointer constant
+                let null = match (BuiltinType::is_pointer(&x.ty), BuiltinType::is_pointer(&op.expr.ty)) {
+                    (true, false) if op.expr.name == "0" => Some(x.name.clone()),
+                    (false, true) if x.name == "0" => Some(op.expr.name.clone()),
+                    _ => None
+                };
+                if let Some(p) = null {
+                    x = Expr::new(format!("{}{}.is_null()", if name == "!=" { "!" } else { "" }, Self::operand(p)), &BuiltinType::Bool.as_id());
+                    continue
+                }
+            }
+
             let matching = op.op().matching;
             let default = &op.op().default;
 
+            if BuiltinType::is_number(&x.ty) && BuiltinType::is_number(&op.expr.ty) && name != "&&" && name != "||" {
+                let is_bool = x.ty == BuiltinType::Bool.as_id() && op.expr.ty == BuiltinType::Bool.as_id();
+                if is_bool && ["==", "!=", "&", "^", "|"].contains(&name) {
+                    // Rust does these on `bool` as well
+                } else {
+                    if name == "<<" || name == ">>" {
+                        // operands of shift are promoted apart
+                        x.convert(&BuiltinType::promote(&x.ty));
+                        op.expr.convert(&BuiltinType::promote(&op.expr.ty))
+                    } else {
+                        let ty = BuiltinType::usual(&x.ty, &op.expr.ty);
+                        x.convert(&ty);
+                        op.expr.convert(&ty)
+                    }
+
+                    if !matching(&x.ty) || !matching(&op.expr.ty) {
+                        panic!("invalid operands to binary `{}` (have `{}` and `{}`)", name, FullType::raw(&x.ty), FullType::raw(&op.expr.ty))
+                    }
+                }
+
+                let ty = (op.op().result)(&x.ty);
+                x = Expr::new(if BuiltinType::is_integer(&x.ty) && ["+", "-", "*", "<<"].contains(&name) {
+                    Overflow::binop(x, name, op.expr)
+                } else {
+                    format!("{} {} {}", x.name, name, op.expr.name)
+                }, &ty);
+                continue
+            }
+
             match BuiltinType::dominant(&x.ty, &op.expr.ty) {
                 Dominant::Similar => if !matching(&x.ty) {
                     x.convert(default);
@@ -189,6 +283,69 @@ impl Binop {
 
         x
     }
+
+    /// `p + i`, `i + p`, `p - i` or `p - q`, where `p` and `q` are pointers;
+    /// the difference of pointers is `ptrdiff_t`
+    fn pointer(x: Expr, op: &str, y: Expr) -> Expr {
+        let pointee = |x: &Expr| {
+            if x.ty.ptr.len() == 1 && x.ty.idx == BuiltinType::Void as usize {
+                panic!("arithmetic on `void *`")
+            }
+            (Op::ops()[Op::find("*", 1)].result)(&x.ty)
+        };
+
+        match (BuiltinType::is_pointer(&x.ty), BuiltinType::is_pointer(&y.ty)) {
+            (true, true) => {
+                if op != "-" {
+                    panic!("invalid operands to binary `{}`", op)
+                } else if pointee(&x) != pointee(&y) {
+                    panic!("`{}` and `{}` are not pointers to compatible types", FullType::raw(&x.ty), FullType::raw(&y.ty))
+                }
+
+                let code = format!("{}.offset_from({})", Self::operand(x.name), y.name.deparentify());
+                Expr::new(format!("({} as i64)", Function::get().unsafe_code(code).parentify()), &BuiltinType::SignedLong.as_id())
+            },
+            (true, false) => {
+                pointee(&x);
+                let code = Self::offset(&x, op, y);
+                Expr::new(Function::get().unsafe_code(code).parentify(), &TypeID {
+                    mutable: true,
+                    ..x.ty
+                })
+            },
+            (false, true) if op == "+" => Self::pointer(y, op, x),
+            _ => panic!("invalid operands to binary `{}`", op)
+        }
+    }
+
+    /// Code moving pointer `p` by `i` elements forward(`+`) or backward(`-`),
+    /// which is not wrapped into `unsafe`
+    pub fn offset(p: &Expr, op: &str, i: Expr) -> String {
+        if !BuiltinType::is_integer(&i.ty) {
+            panic!("pointer can be moved only by integer, not by `{}`", FullType::raw(&i.ty))
+        }
+
+        let p = Self::operand(p.name.clone());
+        let forward = op == "+";
+        if i.name.chars().all(|x| x.is_ascii_digit()) {
+            format!("{}.{}({})", p, if forward { "add" } else { "sub" }, i.name)
+        } else if BuiltinType::is_unsigned(&i.ty) {
+            format!("{}.{}({} as usize)", p, if forward { "add" } else { "sub" }, Self::operand(i.name))
+        } else if forward {
+            format!("{}.offset({} as isize)", p, Self::operand(i.name))
+        } else {
+            format!("{}.offset(-({} as isize))", p, Self::operand(i.name))
+        }
+    }
+
+    /// `x` made usable as the left side of method call or cast
+    pub fn operand(x: String) -> String {
+        if x.contains(' ') {
+            x.parentify()
+        } else {
+            x
+        }
+    }
 }
 
 pub struct Unop;
@@ -200,6 +357,21 @@ impl Unop {
         if !(op.matching)(&x.ty) {
             x.convert(&op.default)
         }
+        if ["+", "-", "~"].contains(&operator) {
+            x.convert(&BuiltinType::promote(&x.ty))
+        }
+
+        if op.name == "-" && BuiltinType::is_integer(&x.ty) {
+            if let Some(value) = Constant::int_literal(&x.name) {
+                // negative literal is a constant too
+                let value = Constant::wrap(-value, &x.ty);
+                return Expr::new(Constant::typed(value.to_string(), value, &x.ty), &x.ty)
+            }
+            let ty = x.ty.clone();
+            return Expr::new(Overflow::neg(x), &ty)
+        } else if ["++a", "++b", "--a", "--b"].contains(&operator) && BuiltinType::is_signed(&x.ty) && Overflow::policy(&x.ty) != "" {
+            return Overflow::step(x, operator)
+        }
 
         let mut content = format!("{}{}", if operator == "~" {
             "!"
@@ -208,29 +380,341 @@ impl Unop {
         }, x.name);
 
         if op.name == "*" {
+            let place = content.clone();
             if Function::get().should_be_safe() {
                 content = format!("unsafe {{ {} }}", content)
             } else {
                 Function::get().flags.remove(FnFlags::SAFE)
             }
-        } else if op.name == "++a" {
-            Function::check_and_make_mutable_on_require(&x.name);
-            content = format!("::qas::builtin::inca(&mut {})", x.name)
-        } else if op.name == "++b" {
-            Function::check_and_make_mutable_on_require(&x.name);
-            content = format!("::qas::builtin::incb(&mut {})", x.name)
-        } else if op.name == "--a" {
-            Function::check_and_make_mutable_on_require(&x.name);
-            content = format!("::qas::builtin::deca(&mut {})", x.name)
-        } else if op.name == "--b" {
-            Function::check_and_make_mutable_on_require(&x.name);
-            content = format!("::qas::builtin::decb(&mut {})", x.name)
+            return Expr::lvalue(content, place, &(op.result)(&x.ty))
+        } else if op.name == "&" {
+            let place = Assign::place(&x);
+            content = if Global::is_static_mut(&place) {
+                // taking the address alone needs no `unsafe`
+                Global::address(&place, x.ty.mutable)
+            } else {
+                Assign::guard(&x, if x.ty.mutable {
+                    Function::check_and_make_mutable_on_require(&place);
+                    format!("(&mut {} as {})", place, FullType::real(&(op.result)(&x.ty)))
+                } else {
+                    format!("(&{} as {})", place, FullType::real(&(op.result)(&x.ty)))
+                })
+            }
+        } else if let Some(f) = match op.name.as_str() {
+            "++a" => Some("inca"),
+            "++b" => Some("incb"),
+            "--a" => Some("deca"),
+            "--b" => Some("decb"),
+            _ => None
+        } {
+            let place = Assign::place(&x);
+            Function::check_and_make_mutable_on_require(&place);
+            let borrow = if Global::is_static_mut(&place) {
+                format!("&mut *{}", Global::address(&place, true))
+            } else {
+                format!("&mut {}", place)
+            };
+            content = Assign::guard(&x, format!("::qas::builtin::{}({})", f, borrow))
         }
 
         Expr::new(content, &(op.result)(&x.ty))
     }
 }
 
+impl Unop {
+    /// `(ty)x`
+    pub fn cast(x: Expr, ty: TypeID) -> Expr {
+        if !ty.dims.is_empty() {
+            panic!("cast to array type `{}`", FullType::raw(&ty))
+        } else if ty == BuiltinType::Void.as_id() {
+            // value is evaluated and thrown away
+            return Expr {
+                void_cast: true,
+                ..Expr::new(format!("{{ let _ = {}; }}", x.name.deparentify()), &ty)
+            }
+        } else if x.ty == BuiltinType::Void.as_id() {
+            panic!("void value cannot be cast to `{}`", FullType::raw(&ty))
+        } else if Record::is_record(&ty) || Record::is_record(&x.ty) {
+            if x.ty != ty {
+                panic!("cannot cast `{}` to `{}`", FullType::raw(&x.ty), FullType::raw(&ty))
+            }
+            return Expr::new(x.name, &ty)
+        }
+
+        Expr::new(BuiltinType::convert(&x.ty, &ty, &x.name), &ty)
+    }
+}
+
+/// Arithmetic, which may overflow: unsigned one wraps around as in C, while
+/// signed one is undefined in C, so that it obeys the policy of the function
+pub struct Overflow;
+
+impl Overflow {
+    /// File of the statement being translated
+    fn file() -> &'static mut String {
+        static mut FILE: String = String::new();
+        unsafe { &mut FILE }
+    }
+
+    /// Files, which the preprocessed code comes from
+    fn files() -> &'static mut Vec <String> {
+        static mut FILES: Vec <String> = Vec::new();
+        unsafe { &mut FILES }
+    }
+
+    /// Offset of start of every line in the preprocessed code, the file
+    /// and the line of the source, which it comes from
+    fn lines() -> &'static mut Vec <(usize, usize, usize)> {
+        static mut LINES: Vec <(usize, usize, usize)> = Vec::new();
+        unsafe { &mut LINES }
+    }
+
+    /// Line of the statement being translated
+    fn line() -> &'static mut usize {
+        static mut LINE: usize = 1;
+        unsafe { &mut LINE }
+    }
+
+    /// Takes the line markers of `Include::marker` away from `code`, remembering
+    /// where every line comes from; `file` is the one given to `qas!`
+    pub fn begin(file: &str, code: String) -> String {
+        *Self::files() = vec![file.to_string()];
+        *Self::lines() = vec![(0, 0, 1)];
+
+        let mut s = String::with_capacity(code.len());
+        // markers stand between pieces of code
+        for (i, part) in code.split('\u{5}').enumerate() {
+            let (_, mut file, mut line) = *Self::lines().last().unwrap();
+            if i % 2 == 1 {
+                let (n, name) = part.split_once('\u{6}').unwrap();
+                file = match Self::files().iter().position(|x| x == name) {
+                    Some(x) => x,
+                    None => {
+                        Self::files().push(name.to_string());
+                        Self::files().len() - 1
+                    }
+                };
+                line = n.parse().unwrap();
+                if Self::lines().last().unwrap().0 == s.len() {
+                    Self::lines().pop();
+                }
+                Self::lines().push((s.len(), file, line));
+                continue
+            }
+
+            for (j, _) in part.match_indices('\n') {
+                line += 1;
+                Self::lines().push((s.len() + j + 1, file, line))
+            }
+            s.push_str(part)
+        }
+        s
+    }
+
+    pub fn at(pos: usize) {
+        let (_, file, line) = Self::lines()[Self::lines().partition_point(|x| x.0 <= pos) - 1];
+        *Self::file() = Self::files()[file].clone();
+        *Self::line() = line
+    }
+
+    /// Prefix of Rust integer method, which does the operation on `ty`,
+    /// or empty string for ordinary operator, which panics in debug builds
+    pub fn policy(ty: &TypeID) -> &'static str {
+        if BuiltinType::is_unsigned(ty) {
+            "wrapping_"
+        } else if !Function::is_in_body() {
+            ""
+        } else if Function::get().flags.contains(FnFlags::WRAP) {
+            "wrapping_"
+        } else if Function::get().flags.intersects(FnFlags::CHECKED | FnFlags::TRAP) {
+            "checked_"
+        } else {
+            ""
+        }
+    }
+
+    /// `x` used as receiver of method call
+    fn receiver(x: &Expr) -> String {
+        match Constant::int_literal(&x.name) {
+            // literal must have type to call methods on it
+            Some(value) if value < 0 => format!("({}{})", Constant::unsuffixed(&x.name), FullType::real(&x.ty)),
+            Some(_) => format!("{}{}", Constant::unsuffixed(&x.name), FullType::real(&x.ty)),
+            None if x.name.chars().all(|x| x.is_alphanumeric() || x == '_' || x == '.') => x.name.clone(),
+            None => x.name.parentify()
+        }
+    }
+
+    fn call(x: &Expr, method: &str, arg: Option <String>) -> String {
+        let policy = Self::policy(&x.ty);
+        let code = format!("{}.{}{}({})", Self::receiver(x), policy, method, arg.unwrap_or_default());
+        if policy == "checked_" && Function::get().flags.contains(FnFlags::TRAP) {
+            format!("{}.expect(\"signed integer overflow\")", code)
+        } else if policy == "checked_" {
+            format!("{}.expect(\"signed integer overflow at {}:{}\")", code, Self::file(), Self::line())
+        } else {
+            code
+        }
+    }
+
+    /// `x op y`, where both are integers of the same type
+    pub fn binop(x: Expr, op: &str, y: Expr) -> String {
+        if Self::policy(&x.ty).is_empty() {
+            return format!("{} {} {}", x.name, op, y.name)
+        }
+
+        let y = if op != "<<" {
+            y.name.deparentify()
+        } else if y.ty == BuiltinType::UnsignedInt.as_id() {
+            y.name.deparentify()
+        } else {
+            BuiltinType::convert(&y.ty, &BuiltinType::UnsignedInt.as_id(), &y.name).deparentify()
+        };
+        Self::call(&x, match op {
+            "+" => "add",
+            "-" => "sub",
+            "*" => "mul",
+            _ => "shl"
+        }, Some(y))
+    }
+
+    /// `-x` for integer `x`
+    pub fn neg(x: Expr) -> String {
+        if Self::policy(&x.ty).is_empty() {
+            format!("-{}", x.name)
+        } else {
+            Self::call(&x, "neg", None)
+        }
+    }
+
+    /// `++` or `--` on signed integer, which follows the policy
+    fn step(x: Expr, operator: &str) -> Expr {
+        let one = Expr::new(String::from("1"), &BuiltinType::SignedInt.as_id());
+        let op = if operator.starts_with("++") { "+=" } else { "-=" };
+        if operator.ends_with('b') {
+            return Assign::value(x, op, one)
+        }
+
+        let code = Assign::once(&x, false, one, |x, one| format!("let __old = {}; {}; __old", Assign::place(x), Assign::assign(x, op, one)));
+        Expr::new(format!("{{ {} }}", code), &x.ty)
+    }
+}
+
+pub struct Assign;
+
+impl Assign {
+    /// Place designated by an lvalue
+    pub fn place(x: &Expr) -> String {
+        match &x.place {
+            Some(place) => place.clone(),
+            None => panic!("lvalue required, but `{}` found", x.name)
+        }
+    }
+
+    /// Whether accessing `x` needs `unsafe`: it is behind a raw pointer, a part
+    /// of `static mut` or, unless it is only written, inside of union
+    fn is_unsafe(x: &Expr, is_write: bool) -> bool {
+        let place = Self::place(x);
+        place.starts_with('*') || place.starts_with("(*") || Global::is_static_mut(&place) || (x.union && !is_write)
+    }
+
+    fn wrap(x: &Expr, is_write: bool, code: String) -> String {
+        if Self::is_unsafe(x, is_write) {
+            Function::get().unsafe_code(code)
+        } else {
+            code
+        }
+    }
+
+    /// Reads the value of `x`
+    pub fn read(x: &Expr) -> String {
+        let code = Self::guard(x, Self::place(x));
+        if code.starts_with("unsafe") {
+            code.parentify()
+        } else {
+            code
+        }
+    }
+
+    /// Wraps code, which reads or changes `x`, into `unsafe` if required
+    pub fn guard(x: &Expr, code: String) -> String {
+        Self::wrap(x, false, code)
+    }
+
+    /// Value, that should be written by `x op y`; if `x` is not converted
+    /// in the middle, the right operand of compound operator is returned too
+    fn value_of(x: &Expr, op: &str, y: Expr) -> (String, Option <String>) {
+        (Op::ops()[Op::find(op, 2)].matching)(&x.ty);
+        Function::check_and_make_mutable_on_require(&Self::place(x));
+
+        if op == "=" {
+            return (BuiltinType::convert(&y.ty, &x.ty, &y.name).deparentify(), None)
+        }
+
+        let binop = &op[..op.len() - 1];
+        let y = Binop::union(Expr::new(x.name.clone(), &x.ty), vec![Operand::new(binop, y, 2)]);
+        let prefix = format!("{} {} ", x.name, binop);
+        let rhs = if y.ty == x.ty && y.name.starts_with(&prefix) {
+            Some(y.name[prefix.len()..].to_string())
+        } else {
+            None
+        };
+        (BuiltinType::convert(&y.ty, &x.ty, &y.name).deparentify(), rhs)
+    }
+
+    /// Whether evaluating `place` changes something, so that it must be evaluated once
+    fn has_side_effects(place: &str) -> bool {
+        place.contains('=') || place.contains("::qas::builtin::inc") || place.contains("::qas::builtin::dec")
+            || Function::fns().iter().any(|f| place.contains(&format!("{}(", f.name)))
+    }
+
+    /// Code made by `f` of `x` and `y`, where the place of `x` is evaluated once:
+    /// if it has side effects, `y` is evaluated first and the place is borrowed as `*__p`
+    pub fn once(x: &Expr, is_write: bool, y: Expr, f: impl FnOnce(&Expr, Expr) -> String) -> String {
+        let place = Self::place(x);
+        if !Self::has_side_effects(&place) {
+            return Self::wrap(x, is_write, f(x, y))
+        }
+
+        Function::check_and_make_mutable_on_require(&place);
+        let borrow = if Global::is_static_mut(&place) {
+            format!("&mut *{}", Global::address(&place, true))
+        } else {
+            format!("&mut {}", place)
+        };
+        let value = format!("let __v: {} = {};", FullType::real(&y.ty), BuiltinType::convert(&y.ty, &y.ty, &y.name).deparentify());
+        let borrow = format!("let __p = {};", Self::guard(x, borrow));
+        let proxy = Expr::lvalue(String::from("*__p"), String::from("*__p"), &x.ty);
+        format!("{} {} {}", value, borrow, f(&proxy, Expr::new(String::from("__v"), &y.ty)))
+    }
+
+    /// `x op y` without `unsafe` and `;`
+    fn assign(x: &Expr, op: &str, y: Expr) -> String {
+        let place = Self::place(x);
+        match Self::value_of(x, op, y) {
+            (_, Some(rhs)) => format!("{} {} {}", place, op, rhs),
+            (value, None) => format!("{} = {}", place, value)
+        }
+    }
+
+    /// `x op y` used as statement
+    pub fn stmt(x: Expr, op: &str, y: Expr) -> String {
+        match Self::once(&x, op == "=", y, |x, y| Self::assign(x, op, y)) {
+            code if code.starts_with("let ") => format!("{{ {} }}", code),
+            code => code + ";"
+        }
+    }
+
+    /// `x op y` used as value, which is the new value of `x`
+    pub fn value(x: Expr, op: &str, y: Expr) -> Expr {
+        let code = Self::once(&x, false, y, |x, y| format!("{}; {}", Self::assign(x, op, y), Self::place(x)));
+        let code = match code {
+            code if code.starts_with("unsafe") => code,
+            code => format!("{{ {} }}", code)
+        };
+        Expr::new(code, &x.ty)
+    }
+}
+
 //         match self {
 //             Self::Deref => if x.ty.ptr != 0 && x.ty.idx != BuiltinType::Void as usize {
 //                 Expr::new(format!("{}", if Function::get().should_be_safe() {
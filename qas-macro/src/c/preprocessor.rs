@@ -1,8 +1,11 @@
 use chrono::{Datelike, Timelike};
+use std::path::{Path, PathBuf};
 
 pub struct Macro {
     pub name: String,
-    pub value: String
+    pub value: String,
+    /// Names of parameters of function-like macro, where `...` is the last one called `__VA_ARGS__`
+    pub params: Option <Vec <String>>
 }
 
 impl Macro {
@@ -20,7 +23,7 @@ impl Macro {
         Self::add("__linux__", "");
 
         unsafe {
-            FILE = format!("\"{}\"", std::fs::canonicalize(file).unwrap().as_path().to_str().unwrap());
+            FILE = format!("{:?}", std::fs::canonicalize(file).unwrap().as_path().to_str().unwrap());
             LINE = 1
         }
 
@@ -60,16 +63,634 @@ impl Macro {
         fn _add(name: String, value: String) {
             match Macro::macros().iter_mut().find(|x| x.name == name) {
                 Some(m) => m.value = value,
-                None => Macro::macros().push(Macro { name, value })
+                None => Macro::macros().push(Macro { name, value, params: None })
             }
         }
 
         _add(name.to_string(), value.to_string())
     }
 
+    /// `#define name(params) value`, which may end with `...`
+    pub fn add_function(name: &str, mut params: Vec <&str>, is_variadic: bool, value: &str) {
+        if (1..params.len()).any(|i| params[i..].contains(&params[i - 1])) {
+            panic!("duplicate macro parameter in `{}`", name)
+        } else if params.contains(&"__VA_ARGS__") {
+            panic!("`__VA_ARGS__` cannot be a parameter of macro `{}`", name)
+        }
+        if is_variadic {
+            params.push("__VA_ARGS__")
+        }
+
+        let params = Some(params.into_iter().map(str::to_string).collect());
+        match Self::macros().iter_mut().find(|x| x.name == name) {
+            Some(m) => {
+                m.value = value.to_string();
+                m.params = params
+            },
+            None => Self::macros().push(Macro { name: name.to_string(), value: value.to_string(), params })
+        }
+    }
+
     pub fn find(name: &str) -> Option <String> {
         Self::macros().iter().find(|x| x.name == name).map(|x| x.value.clone())
     }
+
+    fn get(name: &str) -> Option <&'static Macro> {
+        Self::macros().iter().find(|x| x.name == name)
+    }
+
+    fn is_variadic(&self) -> bool {
+        self.params.as_ref().map_or(false, |x| x.last().map_or(false, |x| x == "__VA_ARGS__"))
+    }
+
+    /// Stands before a name, which is never expanded, because it was met inside of its own value
+    const PAINTED: char = '\u{f}';
+
+    /// Whether the expansion of `text` ends with an invocation of function-like macro, which
+    /// still lacks its arguments: `Some(0)` if they are not opened yet, otherwise
+    /// the number of parentheses to be closed
+    pub fn pending(text: &str) -> Option <usize> {
+        let expanded = Self::expand_hiding(text, &[]);
+        let mut pos = 0;
+        while let Some(c) = expanded[pos..].chars().next() {
+            if c == '"' || c == '\'' {
+                pos += Self::quoted_len(&expanded[pos..]);
+                continue
+            } else if c == Self::PAINTED || !(c.is_ascii_alphabetic() || c == '_') {
+                let len = if c == Self::PAINTED { Self::name_len(&expanded[pos + 1..]) + 1 } else { c.len_utf8() };
+                pos += len;
+                continue
+            }
+
+            let len = Self::name_len(&expanded[pos..]);
+            let after = expanded[pos + len..].trim_start();
+            pos += len;
+            if !Self::get(&expanded[pos - len..pos]).map_or(false, |m| m.params.is_some()) {
+                continue
+            } else if after.is_empty() {
+                return Some(0)
+            } else if after.starts_with('(') {
+                // the arguments, which are closed, would have been expanded
+                let mut depth = 0usize;
+                let mut i = 0;
+                while let Some(c) = after[i..].chars().next() {
+                    match c {
+                        '"' | '\'' => {
+                            i += Self::quoted_len(&after[i..]);
+                            continue
+                        },
+                        '(' => depth += 1,
+                        ')' => depth = depth.saturating_sub(1),
+                        _ => ()
+                    }
+                    i += c.len_utf8()
+                }
+                if depth > 0 {
+                    return Some(depth)
+                }
+            }
+        }
+        None
+    }
+
+    /// Length of the name at the start of `text`
+    fn name_len(text: &str) -> usize {
+        text.find(|x: char| !x.is_ascii_alphanumeric() && x != '_').unwrap_or(text.len())
+    }
+
+    /// Replaces macros in `text`, rescanning their values together with the rest
+    /// of `text`; a macro is not expanded again inside of its own value
+    pub fn expand(text: &str) -> String {
+        Self::expand_hiding(text, &[]).replace(Self::PAINTED, "")
+    }
+
+    fn expand_hiding(text: &str, outer: &[String]) -> String {
+        let mut s = String::new();
+        let mut rest = text.to_string();
+        let mut pos = 0;
+        // name, which is hidden until only that many bytes of `rest` are left
+        let mut hidden: Vec <(String, usize)> = outer.iter().map(|x| (x.clone(), 0)).collect();
+
+        while let Some(c) = rest[pos..].chars().next() {
+            let left = rest.len() - pos;
+            hidden.retain(|x| left > x.1);
+
+            if c == '"' || c == '\'' {
+                let len = Self::quoted_len(&rest[pos..]);
+                s.push_str(&rest[pos..pos + len]);
+                pos += len
+            } else if c == Self::PAINTED {
+                // the name stays as it is, wherever it goes later
+                let len = Self::name_len(&rest[pos + 1..]) + 1;
+                s.push_str(&rest[pos..pos + len]);
+                pos += len
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let len = Self::name_len(&rest[pos..]);
+                let name = rest[pos..pos + len].to_string();
+                let m = match Self::get(&name) {
+                    Some(m) if !hidden.iter().any(|x| x.0 == name) => m,
+                    Some(_) => {
+                        s.push(Self::PAINTED);
+                        s.push_str(&name);
+                        pos += len;
+                        continue
+                    },
+                    _ => {
+                        s.push_str(&match name.as_str() {
+                            "__LINE__" => unsafe { LINE.to_string() },
+                            "__FILE__" => unsafe { FILE.clone() },
+                            _ => name
+                        });
+                        pos += len;
+                        continue
+                    }
+                };
+
+                let (value, end) = match &m.params {
+                    None => (Self::substitute(m, &[], Vec::new(), &[]), pos + len),
+                    Some(params) => match Self::arguments(&rest[pos + len..]) {
+                        Some((args, used)) => {
+                            let names = hidden.iter().map(|x| x.0.clone()).collect::<Vec <_>>();
+                            (Self::substitute(m, params, args, &names), pos + len + used)
+                        },
+                        // function-like macro without arguments is just a name
+                        None => {
+                            s.push_str(&name);
+                            pos += len;
+                            continue
+                        }
+                    }
+                };
+
+                hidden.push((name, rest.len() - end));
+                rest = value + &rest[end..];
+                pos = 0
+            } else if c.is_ascii_digit() {
+                // suffixes and exponents are not names
+                let len = rest[pos..].find(|x: char| !x.is_ascii_alphanumeric() && x != '_' && x != '.').unwrap_or(left);
+                s.push_str(&rest[pos..pos + len]);
+                pos += len
+            } else {
+                s.push(c);
+                pos += c.len_utf8()
+            }
+        }
+        s
+    }
+
+    /// Arguments in parentheses at the start of `text` and the length of the whole list
+    fn arguments(text: &str) -> Option <(Vec <String>, usize)> {
+        let start = text.len() - text.trim_start().len();
+        if !text[start..].starts_with('(') {
+            return None
+        }
+
+        let mut args = Vec::new();
+        let mut arg = String::new();
+        let mut depth = 0;
+        let mut i = start;
+        while i < text.len() {
+            let c = text[i..].chars().next().unwrap();
+            let len = match c {
+                '"' | '\'' => Self::quoted_len(&text[i..]),
+                _ => c.len_utf8()
+            };
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 1 => {
+                    args.push(arg.trim().to_string());
+                    return Some((args, i + 1))
+                },
+                ')' => depth -= 1,
+                ',' if depth == 1 => {
+                    args.push(arg.trim().to_string());
+                    arg.clear();
+                    i += 1;
+                    continue
+                },
+                _ => ()
+            }
+            if i > start {
+                arg.push_str(&text[i..i + len])
+            }
+            i += len
+        }
+        None
+    }
+
+    /// Tokens of macro value, where runs of whitespace are tokens too
+    fn tokens(text: &str) -> Vec <&str> {
+        let mut tokens = Vec::new();
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let len = if c == '"' || c == '\'' {
+                Self::quoted_len(rest)
+            } else if c.is_ascii_alphanumeric() || c == '_' {
+                rest.find(|x: char| !x.is_ascii_alphanumeric() && x != '_').unwrap_or(rest.len())
+            } else if c.is_whitespace() {
+                rest.find(|x: char| !x.is_whitespace()).unwrap_or(rest.len())
+            } else if rest.starts_with("##") {
+                2
+            } else {
+                c.len_utf8()
+            };
+            tokens.push(&rest[..len]);
+            rest = &rest[len..]
+        }
+        tokens
+    }
+
+    /// `#x`: argument as string literal, where whitespace is a single space
+    fn stringify(arg: &str) -> String {
+        let mut s = String::from("\"");
+        for x in Self::tokens(arg) {
+            if x.starts_with(char::is_whitespace) {
+                s.push(' ')
+            } else if x.starts_with('"') || x.starts_with('\'') || x.starts_with("L\"") {
+                s.push_str(&x.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                s.push_str(x)
+            }
+        }
+        s + "\""
+    }
+
+    /// Value of macro, where parameters are replaced by arguments: they are expanded first
+    /// unless they are operands of `#` or `##`; then operands of `##` are pasted together
+    fn substitute(m: &Macro, params: &[String], mut args: Vec <String>, hidden: &[String]) -> String {
+        if params.is_empty() && args.len() == 1 && args[0].is_empty() {
+            args.clear()
+        }
+        if m.is_variadic() && args.len() >= params.len() {
+            // the rest of arguments are one with commas
+            let rest = args.split_off(params.len() - 1);
+            args.push(rest.join(", "))
+        } else if m.is_variadic() && args.len() + 1 == params.len() {
+            args.push(String::new())
+        }
+        if args.len() != params.len() {
+            panic!("macro `{}` requires {} arguments, but {} given", m.name, params.len(), args.len())
+        }
+        let has_va_args = m.is_variadic() && !args[params.len() - 1].is_empty();
+        // `__VA_OPT__` looks at the variable arguments, after they are expanded
+        let has_va_opt = m.is_variadic() && !Self::expand_hiding(&args[params.len() - 1], hidden).trim().is_empty();
+
+        let mut tokens = Self::tokens(&m.value);
+        // `__VA_OPT__(x)` is `x` only if there are variable arguments
+        while let Some(i) = tokens.iter().position(|x| *x == "__VA_OPT__") {
+            if !m.is_variadic() {
+                panic!("`__VA_OPT__` can appear only in variadic macro `{}`", m.name)
+            }
+            let open = (i + 1..tokens.len()).find(|&j| !tokens[j].starts_with(char::is_whitespace));
+            if open.map_or(true, |j| tokens[j] != "(") {
+                panic!("`__VA_OPT__` must be followed by `(` in macro `{}`", m.name)
+            }
+            let open = open.unwrap();
+            let mut depth = 0;
+            let close = (open..tokens.len()).find(|&j| {
+                match tokens[j] {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => ()
+                }
+                depth == 0
+            }).unwrap_or_else(|| panic!("unterminated `__VA_OPT__` in macro `{}`", m.name));
+
+            let inner = if has_va_opt { tokens[open + 1..close].to_vec() } else { vec![""] };
+            tokens.splice(i..=close, inner);
+        }
+
+        let is_space = |x: &str| x.starts_with(char::is_whitespace);
+        let neighbour = |i: usize, step: isize| {
+            let mut j = i as isize + step;
+            while j >= 0 && (j as usize) < tokens.len() && is_space(tokens[j as usize]) {
+                j += step
+            }
+            tokens.get(j as usize).copied()
+        };
+        let param = |x: &str| params.iter().position(|y| y == x);
+
+        // `None` stands for `##`
+        let mut items: Vec <Option <String>> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let x = tokens[i];
+            if x == "#" && m.params.is_some() {
+                let mut j = i + 1;
+                while j < tokens.len() && is_space(tokens[j]) {
+                    j += 1
+                }
+                match tokens.get(j).and_then(|x| param(x)) {
+                    Some(k) => items.push(Some(Self::stringify(&args[k].replace(Self::PAINTED, "")))),
+                    None => panic!("`#` is not followed by a parameter of macro `{}`", m.name)
+                }
+                i = j + 1;
+                continue
+            }
+
+            if x == "##" && !has_va_args && neighbour(i, -1) == Some(",") && neighbour(i, 1) == Some("__VA_ARGS__") && m.is_variadic() {
+                // GNU `, ## __VA_ARGS__` swallows the comma, if there are no variable arguments
+                while items.last().map_or(false, |x| x.as_deref().map_or(false, is_space)) {
+                    items.pop();
+                }
+                items.pop();
+                i += 1;
+                continue
+            }
+
+            items.push(match param(x) {
+                _ if x == "##" => None,
+                // pasting makes new names, which may be expanded
+                Some(k) if neighbour(i, -1) == Some("##") || neighbour(i, 1) == Some("##") => Some(args[k].replace(Self::PAINTED, "")),
+                Some(k) => Some(Self::expand_hiding(&args[k], hidden)),
+                None => Some(x.to_string())
+            });
+            i += 1
+        }
+
+        if matches!(items.first(), Some(None)) || matches!(items.last(), Some(None)) {
+            panic!("`##` cannot be at either end of macro `{}`", m.name)
+        }
+
+        let mut s = String::new();
+        let mut is_pasting = false;
+        for x in items {
+            match x {
+                None => {
+                    s.truncate(s.trim_end().len());
+                    is_pasting = true
+                },
+                Some(x) if is_pasting && is_space(&x) => (),
+                Some(x) => {
+                    s.push_str(&x);
+                    is_pasting = false
+                }
+            }
+        }
+        s
+    }
+
+    /// Length of string or character literal at the start of `text`
+    fn quoted_len(text: &str) -> usize {
+        let quote = text.as_bytes()[0];
+        let mut i = 1;
+        while i < text.len() && text.as_bytes()[i] != quote {
+            i += if text.as_bytes()[i] == b'\\' { 2 } else { 1 }
+        }
+        (i + 1).min(text.len())
+    }
+
+    /// Condition of `#if` or `#elif`: `defined` is replaced first, then macros
+    /// are expanded and the rest of names are zeros
+    pub fn condition(text: &str) -> bool {
+        let mut s = String::new();
+        let mut rest = text;
+
+        while let Some(idx) = rest.find("defined") {
+            let is_word = |x: Option <char>| x.map_or(true, |x| !x.is_ascii_alphanumeric() && x != '_');
+            if !is_word(rest[..idx].chars().next_back()) || !is_word(rest[idx + 7..].chars().next()) {
+                s.push_str(&rest[..idx + 7]);
+                rest = &rest[idx + 7..];
+                continue
+            }
+
+            s.push_str(&rest[..idx]);
+            let after = rest[idx + 7..].trim_start();
+            let (inner, parens) = match after.strip_prefix('(') {
+                Some(x) => (x.trim_start(), true),
+                None => (after, false)
+            };
+            let len = inner.find(|x: char| !x.is_ascii_alphanumeric() && x != '_').unwrap_or(inner.len());
+            if len == 0 {
+                panic!("`defined` without macro name in `#if {}`", text.trim())
+            }
+
+            s.push_str(if Self::is_defined(&inner[..len]) { " 1 " } else { " 0 " });
+            rest = &inner[len..];
+            if parens {
+                rest = rest.trim_start().strip_prefix(')').unwrap_or_else(|| panic!("missing `)` after `defined` in `#if {}`", text.trim()))
+            }
+        }
+        s.push_str(rest);
+
+        match super::clang::condition(&Self::expand(&s)) {
+            Ok(Ok(x)) => x.value != 0,
+            Ok(Err(e)) => panic!("{} in `#if {}`", e, text.trim()),
+            Err(e) => panic!("invalid `#if {}`: {}", text.trim(), e)
+        }
+    }
+}
+
+/// Value of `#if` expression, where every integer is either `intmax_t` or `uintmax_t`
+#[derive(Debug, Clone, Copy)]
+pub struct MaxInt {
+    pub value: i64,
+    pub unsigned: bool
+}
+
+/// Value, which is an error message, if evaluation failed like on division by zero
+pub type MaxIntResult = Result <MaxInt, String>;
+
+impl MaxInt {
+    pub fn new(value: i64, unsigned: bool) -> MaxIntResult {
+        Ok(MaxInt { value, unsigned })
+    }
+
+    pub fn boolean(x: bool) -> MaxIntResult {
+        Self::new(x as i64, false)
+    }
+
+    /// Integer literal, which becomes unsigned if it does not fit `intmax_t`
+    pub fn literal(x: &str, unsigned: bool) -> MaxIntResult {
+        match super::Constant::int_literal(x) {
+            Some(x) if x <= i64::MAX as i128 => Self::new(x as i64, unsigned),
+            Some(x) if x <= u64::MAX as i128 => Self::new(x as u64 as i64, true),
+            _ => Err(format!("integer constant `{}` is too large", x))
+        }
+    }
+
+    pub fn unop(op: &str, x: MaxIntResult) -> MaxIntResult {
+        let x = x?;
+        match op {
+            "-" => Self::new(x.value.wrapping_neg(), x.unsigned),
+            "~" => Self::new(!x.value, x.unsigned),
+            "!" => Self::boolean(x.value == 0),
+            _ => Ok(x)
+        }
+    }
+
+    /// `x op y`, where `&&` and `||` do not care about errors in the operand, which is not evaluated
+    pub fn binop(x: MaxIntResult, op: &str, y: MaxIntResult) -> MaxIntResult {
+        match op {
+            "&&" => return if x?.value == 0 { Self::boolean(false) } else { Self::boolean(y?.value != 0) },
+            "||" => return if x?.value != 0 { Self::boolean(true) } else { Self::boolean(y?.value != 0) },
+            _ => ()
+        }
+
+        let (x, y) = (x?, y?);
+        if op == "<<" || op == ">>" {
+            // the type is the one of the left operand; negative or too large count shifts out everything
+            let count = if y.unsigned || y.value >= 0 { y.value as u64 } else { u64::MAX };
+            let value = if count >= 64 {
+                if op == ">>" && !x.unsigned && x.value < 0 { -1 } else { 0 }
+            } else if op == "<<" {
+                x.value << count
+            } else if x.unsigned {
+                ((x.value as u64) >> count) as i64
+            } else {
+                x.value >> count
+            };
+            return Self::new(value, x.unsigned)
+        }
+
+        let unsigned = x.unsigned || y.unsigned;
+        let (a, b) = (x.value, y.value);
+        if (op == "/" || op == "%") && b == 0 {
+            return Err(String::from("division by zero"))
+        }
+
+        match op {
+            "+" => Self::new(a.wrapping_add(b), unsigned),
+            "-" => Self::new(a.wrapping_sub(b), unsigned),
+            "*" => Self::new(a.wrapping_mul(b), unsigned),
+            "/" if unsigned => Self::new(((a as u64) / (b as u64)) as i64, true),
+            "/" => Self::new(a.wrapping_div(b), false),
+            "%" if unsigned => Self::new(((a as u64) % (b as u64)) as i64, true),
+            "%" => Self::new(a.wrapping_rem(b), false),
+            "&" => Self::new(a & b, unsigned),
+            "^" => Self::new(a ^ b, unsigned),
+            "|" => Self::new(a | b, unsigned),
+            "==" => Self::boolean(a == b),
+            "!=" => Self::boolean(a != b),
+            _ => Self::boolean(match (op, unsigned) {
+                ("<", true) => (a as u64) < (b as u64),
+                ("<=", true) => (a as u64) <= (b as u64),
+                (">", true) => (a as u64) > (b as u64),
+                (">=", true) => (a as u64) >= (b as u64),
+                ("<", _) => a < b,
+                ("<=", _) => a <= b,
+                (">", _) => a > b,
+                _ => a >= b
+            })
+        }
+    }
+
+    /// `c ? x : y`, where the result has the common type of `x` and `y`
+    pub fn ternary(c: MaxIntResult, x: MaxIntResult, y: MaxIntResult) -> MaxIntResult {
+        let unsigned = x.as_ref().map_or(false, |x| x.unsigned) || y.as_ref().map_or(false, |y| y.unsigned);
+        let value = if c?.value != 0 { x? } else { y? };
+        Self::new(value.value, unsigned)
+    }
+}
+
+/// State of `#include`, which is shared by all the included files
+pub struct Include;
+
+impl Include {
+    /// Limit of nested includes, which catches include cycles
+    const MAX_DEPTH: usize = 200;
+
+    /// Directories to search for included files, given to `qas!`
+    fn dirs() -> &'static mut Vec <PathBuf> {
+        static mut DIRS: Vec <PathBuf> = Vec::new();
+        unsafe { &mut DIRS }
+    }
+
+    /// Files being preprocessed, where the last one is the current file
+    fn stack() -> &'static mut Vec <PathBuf> {
+        static mut STACK: Vec <PathBuf> = Vec::new();
+        unsafe { &mut STACK }
+    }
+
+    /// Files with `#pragma once`, which are not included again
+    fn once() -> &'static mut Vec <PathBuf> {
+        static mut ONCE: Vec <PathBuf> = Vec::new();
+        unsafe { &mut ONCE }
+    }
+
+    /// Name of the current file, which diagnostics of the generated code refer to
+    pub fn name() -> &'static mut String {
+        static mut NAME: String = String::new();
+        unsafe { &mut NAME }
+    }
+
+    pub fn begin(file: &str, dirs: Vec <String>) {
+        *Self::dirs() = dirs.into_iter().map(PathBuf::from).collect();
+        *Self::stack() = vec![Self::canonical(Path::new(file))];
+        *Self::name() = file.to_string();
+        Self::once().clear()
+    }
+
+    /// Stands in the preprocessed code, where the lines of the current file
+    /// start from `line`; `Overflow::begin` takes it away
+    pub fn marker(line: usize) -> String {
+        format!("\u{5}{}\u{6}{}\u{5}", line, Self::name())
+    }
+
+    fn canonical(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Finds the file of `#include "name"` or `#include <name>`; the former is
+    /// searched next to the current file first, then both are searched in the directories
+    fn resolve(name: &str, is_angled: bool) -> PathBuf {
+        let current = Self::stack().last().and_then(|x| x.parent()).map(Path::to_path_buf);
+        let path = Path::new(name);
+        if path.is_absolute() {
+            return path.to_path_buf()
+        }
+
+        let mut dirs = Vec::new();
+        if !is_angled {
+            dirs.extend(current)
+        }
+        dirs.extend(Self::dirs().iter().cloned());
+
+        match dirs.iter().map(|x| x.join(path)).find(|x| x.is_file()) {
+            Some(x) => Self::canonical(&x),
+            None if is_angled => panic!("cannot find `<{}>` in include directories {:?}", name, Self::dirs()),
+            None => panic!("cannot find `\"{}\"` next to {:?} or in include directories {:?}", name, Self::stack().last().unwrap(), Self::dirs())
+        }
+    }
+
+    /// Preprocessed content of the included file, where `__FILE__` and `__LINE__`
+    /// refer to that file until it ends
+    pub fn file(name: &str, is_angled: bool) -> String {
+        let path = Self::resolve(name, is_angled);
+        if Self::once().contains(&path) {
+            return String::new()
+        } else if Self::stack().len() > Self::MAX_DEPTH {
+            let start = Self::stack().iter().rposition(|x| *x == path).unwrap_or(0);
+            let cycle: Vec <_> = Self::stack()[start..].iter().chain([&path]).map(|x| x.display().to_string()).collect();
+            panic!("#include nested too deeply, include cycle: {}", cycle.join(" -> "))
+        }
+
+        let code = crate::read_file(path.to_str().unwrap());
+        let saved = unsafe {
+            let file = std::mem::replace(&mut FILE, format!("{:?}", path.display().to_string()));
+            (file, std::mem::replace(&mut LINE, 1), std::mem::replace(Self::name(), path.display().to_string()))
+        };
+        let start = Self::marker(1);
+        Self::stack().push(path);
+
+        let s = super::preprocess_file(code);
+
+        Self::stack().pop();
+        unsafe {
+            FILE = saved.0;
+            LINE = saved.1
+        }
+        *Self::name() = saved.2;
+        format!("{}{}{}{}", start, s, if s.ends_with('\n') { "" } else { "\n" }, Self::marker(saved.1))
+    }
+
+    /// `#pragma once` in the current file
+    pub fn pragma_once() {
+        let path = Self::stack().last().unwrap().clone();
+        if !Self::once().contains(&path) {
+            Self::once().push(path)
+        }
+    }
 }
 
 pub static mut LINE: usize = 1;
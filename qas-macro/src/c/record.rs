@@ -0,0 +1,244 @@
+use crate::StringExt;
+use check_keyword::CheckKeyword;
+use super::{FullType, TypeID, AliasType, Function, Expr, Unop, Assign, Tab};
+
+/// Member of a struct or union
+pub struct Field {
+    /// Empty for anonymous struct or union member, whose fields are accessed
+    /// as if they were declared in place of it
+    pub name: String,
+    pub ty: TypeID
+}
+
+pub struct Record;
+
+impl Record {
+    /// Position in code of every definition met so far and the type it defines,
+    /// so that parsing the same definition again does not define it twice
+    fn defined() -> &'static mut Vec <(usize, usize)> {
+        static mut DEFINED: Vec <(usize, usize)> = Vec::new();
+        unsafe { &mut DEFINED }
+    }
+
+    /// Forgets the definitions of the previous file
+    pub fn reset() {
+        Self::defined().clear();
+        Self::pending().clear()
+    }
+
+    /// Rust items for the definitions, which are not emitted yet
+    pub fn pending() -> &'static mut Vec <String> {
+        static mut PENDING: Vec <String> = Vec::new();
+        unsafe { &mut PENDING }
+    }
+
+    #[inline]
+    pub fn is_record(ty: &TypeID) -> bool {
+        ty.ptr.is_empty() && (FullType::types()[ty.idx].raw.starts_with("struct ") || Self::is_union(ty))
+    }
+
+    #[inline]
+    pub fn is_union(ty: &TypeID) -> bool {
+        ty.ptr.is_empty() && FullType::types()[ty.idx].raw.starts_with("union ")
+    }
+
+    #[inline]
+    pub fn is_anonymous(ty: &TypeID) -> bool {
+        Self::is_record(ty) && FullType::types()[ty.idx].real.starts_with("__Anon")
+    }
+
+    /// Type `kind name` visible here, where `kind` is `struct` or `union`;
+    /// mentioning unknown tag declares it
+    pub fn tagged(kind: &str, name: &str) -> usize {
+        match AliasType::find(&format!("{} {}", kind, name)) {
+            Some(id) => id.idx,
+            None => Self::declare(kind, Some(name))
+        }
+    }
+
+    fn declare(kind: &str, name: Option <&str>) -> usize {
+        let idx = FullType::types().len();
+        let (tag, real) = match name {
+            Some(x) => (x.to_string(), x.to_string().into_safe()),
+            None => (format!("__Anon{}", idx), format!("__Anon{}", idx))
+        };
+
+        FullType::types().push(FullType {
+            raw: format!("{} {}", kind, tag),
+            real,
+            size: 0,
+            align: 1,
+            fields: Vec::new(),
+            complete: false
+        });
+        if let Some(name) = name {
+            AliasType::add(format!("{} {}", kind, name), TypeID::from(idx, true))
+        }
+        idx
+    }
+
+    /// Starts the definition found at `pos`; the type is known from here on,
+    /// so that its fields may point to it
+    pub fn begin(pos: usize, kind: &str, name: Option <&str>) -> usize {
+        if let Some(x) = Self::defined().iter().find(|x| x.0 == pos) {
+            return x.1
+        }
+
+        let declared = name.and_then(|name| {
+            let tag = format!("{} {}", kind, name);
+            AliasType::types().iter().rev().find(|x| x.name == tag && x.is_in_function == Function::is_in_body())
+        });
+        let idx = match declared {
+            Some(x) if FullType::types()[x.id.idx].complete => panic!("redefinition of `{}`", x.name),
+            Some(x) => x.id.idx,
+            None => Self::declare(kind, name)
+        };
+
+        Self::defined().push((pos, idx));
+        idx
+    }
+
+    /// Completes the type started by `begin`, laying out its fields
+    /// one after another or, for union, all at the start
+    pub fn define(idx: usize, fields: Vec <Field>) -> usize {
+        if FullType::types()[idx].complete {
+            return idx
+        }
+
+        let is_union = Self::is_union(&TypeID::from(idx, true));
+
+        let mut size = 0;
+        let mut align = 1;
+        for (i, x) in fields.iter().enumerate() {
+            if !x.name.is_empty() && (fields[..i].iter().any(|y| y.name == x.name) || Self::find(&fields[..i], &x.name).is_some()) {
+                panic!("duplicate member `{}`", x.name)
+            }
+
+            let (x_size, x_align) = FullType::layout(&x.ty);
+            size = if is_union {
+                size.max(x_size)
+            } else {
+                (size + x_align - 1) / x_align * x_align + x_size
+            };
+            align = align.max(x_align)
+        }
+
+        let ty = &mut FullType::types()[idx];
+        ty.size = (size + align - 1) / align * align;
+        ty.align = align;
+        ty.fields = fields;
+        ty.complete = true;
+
+        Self::pending().push(Self::item(idx));
+        idx
+    }
+
+    /// Rust struct or union for the type, checked to have the same layout at compile time
+    fn item(idx: usize) -> String {
+        let x = &FullType::types()[idx];
+        let tabs = Tab::tabs();
+
+        let mut fields = String::new();
+        for (i, field) in x.fields.iter().enumerate() {
+            fields.push_str(&format!("\n{}\tpub {}: {},", tabs, Self::field_name(field, i), FullType::real(&field.ty)))
+        }
+
+        let name = x.real.trim_start_matches('_');
+        let is_camel_case = name.starts_with(|x: char| x.is_ascii_uppercase()) && !name.contains('_');
+
+        format!("#[repr(C)]\n{tabs}#[derive(Clone, Copy)]\n{tabs}{}pub {} {name} {{{}\n{tabs}}}\n{tabs}\
+            unsafe impl ::qas::prelude::Zeroed for {name} {{}}\n{tabs}\
+            const _: () = assert!(::core::mem::size_of::<{name}>() == {} && ::core::mem::align_of::<{name}>() == {});",
+            if is_camel_case { String::new() } else { format!("#[allow(non_camel_case_types)]\n{}", tabs) },
+            &x.raw[..x.raw.find(' ').unwrap()], fields, x.size, x.align, name = x.real, tabs = tabs)
+    }
+
+    pub fn field_name(x: &Field, idx: usize) -> String {
+        if x.name.is_empty() {
+            format!("__anon{}", idx)
+        } else {
+            x.name.clone().into_safe()
+        }
+    }
+
+    /// Path to the field `name` through anonymous members, its type
+    /// and whether one of the anonymous members is a union
+    fn find(fields: &[Field], name: &str) -> Option <(String, TypeID, bool)> {
+        for (i, x) in fields.iter().enumerate() {
+            if x.name == name {
+                return Some((Self::field_name(x, i), x.ty.clone(), false))
+            } else if x.name.is_empty() {
+                if let Some((path, ty, union)) = Self::find(&FullType::types()[x.ty.idx].fields, name) {
+                    return Some((format!("{}.{}", Self::field_name(x, i), path), ty, union || Self::is_union(&x.ty)))
+                }
+            }
+        }
+        None
+    }
+
+    /// Puts the pending definitions before `stmt`
+    pub fn flush(stmt: String) -> String {
+        if Self::pending().is_empty() {
+            return stmt
+        }
+
+        let mut items = Self::pending().drain(..).collect::<Vec <_>>();
+        if !stmt.is_empty() {
+            items.push(stmt)
+        }
+        items.join(&Tab::tabs_nl())
+    }
+
+    /// `x.name`
+    pub fn member(x: Expr, name: &str) -> Expr {
+        if !Self::is_record(&x.ty) {
+            panic!("request for member `{}` in something not a struct or union", name)
+        }
+
+        let (path, ty, through_union) = match Self::find(&FullType::types()[x.ty.idx].fields, name) {
+            Some(x) => x,
+            None => panic!("no member named `{}` in `{}`", name, FullType::types()[x.ty.idx].raw)
+        };
+        let ty = TypeID {
+            mutable: ty.mutable && x.ty.mutable,
+            ..ty
+        };
+
+        match x.place {
+            Some(place) => {
+                let place = format!("{}.{}", if place.starts_with('*') {
+                    format!("({})", place)
+                } else {
+                    place
+                }, path);
+                let mut x = Expr {
+                    union: x.union || through_union || Self::is_union(&x.ty),
+                    ..Expr::lvalue(String::new(), place, &ty)
+                };
+                x.name = Assign::read(&x);
+                x
+            },
+            None => {
+                // field of temporary union is read right away
+                let code = format!("{}.{}", if x.name.contains(' ') {
+                    x.name.parentify()
+                } else {
+                    x.name
+                }, path);
+                Expr::new(if through_union || Self::is_union(&x.ty) {
+                    Function::get().unsafe_code(code).parentify()
+                } else {
+                    code
+                }, &ty)
+            }
+        }
+    }
+
+    /// `x->name`
+    pub fn arrow(x: Expr, name: &str) -> Expr {
+        if x.ty.ptr.is_empty() {
+            panic!("member reference type `{}` is not a pointer", FullType::raw(&x.ty))
+        }
+        Self::member(Unop::union(x, "*"), name)
+    }
+}
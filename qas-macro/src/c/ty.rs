@@ -1,15 +1,17 @@
-use super::{BuiltinType, Function};
+use super::{BuiltinType, Function, BitPtrIter, Field};
 
 #[derive(Debug, Clone, Eq)]
 pub struct TypeID {
     pub idx: usize,
     pub ptr: bit_vec::BitVec <u8>,
-    pub mutable: bool
+    pub mutable: bool,
+    /// Sizes of array dimensions, outermost first, around the type described by the rest
+    pub dims: Vec <usize>
 }
 
 impl PartialEq for TypeID {
     fn eq(&self, other: &Self) -> bool {
-        self.idx == other.idx && self.ptr == other.ptr
+        self.idx == other.idx && self.ptr == other.ptr && self.dims == other.dims
     }
 }
 
@@ -18,22 +20,56 @@ impl TypeID {
         Self {
             idx,
             ptr: Default::default(),
-            mutable
+            mutable,
+            dims: Vec::new()
         }
     }
 
-    pub fn mutable(&self) -> Self {
+    /// Applies pointer levels written after a type, e.g. `* const *`;
+    /// each of `ptr` tells whether that level is mutable
+    pub fn with_ptr(&self, ptr: Vec <bool>) -> Self {
+        if ptr.is_empty() {
+            return self.clone()
+        } else if !self.dims.is_empty() {
+            panic!("pointers to arrays are not supported")
+        }
+
+        let mut levels = BitPtrIter::append(self.ptr.clone(), self.mutable);
+        levels = BitPtrIter::append_many(levels, ptr);
+
+        let mutable = levels.pop().unwrap();
         Self {
-            mutable: true,
-            ..self.clone()
+            idx: self.idx,
+            ptr: levels,
+            mutable,
+            dims: Vec::new()
         }
     }
+
+    /// Array of `dims` made of this type
+    pub fn with_dims(&self, dims: Vec <usize>) -> Self {
+        let mut x = self.clone();
+        x.dims.splice(0..0, dims);
+        x
+    }
+
+    /// Type of array element
+    pub fn element(&self) -> Self {
+        let mut x = self.clone();
+        x.dims.remove(0);
+        x
+    }
 }
 
 pub struct FullType {
     pub raw:  String,
     pub real: String,
-    pub size: usize
+    pub size: usize,
+    pub align: usize,
+    /// Members of struct, empty for other types
+    pub fields: Vec <Field>,
+    /// `false` for struct, which is declared, but not defined yet
+    pub complete: bool
 }
 
 impl FullType {
@@ -44,30 +80,47 @@ impl FullType {
     }
 
     pub fn add_builtin(ty: &str, size: usize) {
-        Self::types().push(Self { raw: String::new(), real: ty.to_string(), size })
+        Self::types().push(Self {
+            raw: String::new(),
+            real: ty.to_string(),
+            size,
+            align: size.max(1),
+            fields: Vec::new(),
+            complete: true
+        })
     }
 
     pub fn real(ty: &TypeID) -> String {
+        if !ty.dims.is_empty() {
+            return format!("[{}; {}]", Self::real(&ty.element()), ty.dims[0])
+        }
+
         format!("{}{}", if ty.ptr.is_empty() {
             String::new()
         } else {
+            // outermost level goes first
             let mut s = String::new();
-            let mut i = 0;
-            while i < ty.ptr.len() {
+            let mut i = ty.ptr.len();
+            while i > 0 {
+                i -= 1;
                 s.push('*');
                 if ty.ptr[i] {
                     s.push_str("mut")
                 } else {
                     s.push_str("const")
                 }
-                s.push(' ');
-                i += 1
+                s.push(' ')
             }
             s
         }, &Self::types()[ty.idx].real)
     }
 
     pub fn raw(ty: &TypeID) -> String {
+        if !ty.dims.is_empty() {
+            return format!("{}{}", Self::raw(&TypeID { dims: Vec::new(), ..ty.clone() }),
+                ty.dims.iter().map(|x| format!("[{}]", x)).collect::<String>())
+        }
+
         format!("{}{}",
             if ty.mutable { "" } else { "const " },
             if BuiltinType::is_builtin(ty) {
@@ -79,7 +132,25 @@ impl FullType {
     }
 
     pub fn size(ty: &TypeID) -> usize {
-        Self::types()[ty.idx].size
+        Self::layout(ty).0
+    }
+
+    /// Size and alignment of the type, as `#[repr(C)]` lays it out
+    pub fn layout(ty: &TypeID) -> (usize, usize) {
+        if !ty.dims.is_empty() {
+            let (size, align) = Self::layout(&ty.element());
+            return (size * ty.dims[0], align)
+        }
+        if !ty.ptr.is_empty() {
+            let size = Self::types()[BuiltinType::usized() as usize].size;
+            return (size, size)
+        }
+
+        let x = &Self::types()[ty.idx];
+        if !x.complete {
+            panic!("incomplete type `{}` used by value", x.raw)
+        }
+        (x.size, x.align)
     }
 }
 
@@ -100,12 +171,12 @@ impl AliasType {
         Self::types().push(Self {
             name,
             id,
-            is_in_function: Function::fns().len() != 0
+            is_in_function: Function::is_in_body()
         })
     }
 
     pub fn find(name: &str) -> Option <&'static TypeID> {
-        Self::types().iter().find(|x| x.name == name).map(|x| &x.id)
+        Self::types().iter().rev().find(|x| x.name == name).map(|x| &x.id)
     }
 
     pub fn clear() {
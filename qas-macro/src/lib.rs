@@ -7,21 +7,46 @@ mod string_ext;
 
 pub(crate) use string_ext::*;
 
-use proc_macro::TokenStream;
+use proc_macro::{TokenStream, TokenTree, Delimiter};
 
+/// `qas!("file.c")` or `qas!("file.c", include = ["dir", ...])`,
+/// where the directories are searched by `#include`
 #[proc_macro]
-pub fn qas(path: TokenStream) -> TokenStream {
-    let path = path.to_string();
-    let path = path.trim();
-
-    assert!(path[..1].chars().next().unwrap() == '"' && path[path.len() - 1..].chars().next().unwrap() == '"', "input should be a string");
-    let path = &path[1..path.len() - 1];
+pub fn qas(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let path = match input.next() {
+        Some(TokenTree::Literal(x)) => unquote(x.to_string()),
+        _ => panic!("input should be a string")
+    };
+
+    let mut include_dirs = Vec::new();
+    match input.next() {
+        None => (),
+        Some(TokenTree::Punct(x)) if x.as_char() == ',' => match (input.next(), input.next(), input.next()) {
+            (None, _, _) => (),
+            (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(eq)), Some(TokenTree::Group(dirs)))
+                if name.to_string() == "include" && eq.as_char() == '=' && dirs.delimiter() == Delimiter::Bracket => {
+                for x in dirs.stream() {
+                    match x {
+                        TokenTree::Literal(x) => include_dirs.push(unquote(x.to_string())),
+                        TokenTree::Punct(x) if x.as_char() == ',' => (),
+                        _ => panic!("include directories should be strings")
+                    }
+                }
+            },
+            _ => panic!("expected `include = [\"dir\", ...]` after the file")
+        },
+        _ => panic!("expected `,` after the file")
+    }
 
     let code = read_file(&path);
 
-    let c = c::start(path, code);
-    println!("{}", c);
-    c.parse().unwrap()
+    c::start(&path, include_dirs, code).parse().unwrap()
+}
+
+fn unquote(s: String) -> String {
+    assert!(s.len() >= 2 && s.starts_with('"') && s.ends_with('"'), "input should be a string");
+    s[1..s.len() - 1].to_string()
 }
 
 pub(crate) fn read_file(file: &str) -> String {
@@ -2,7 +2,7 @@
 //! This module contains different functions implemented as builtin in C
 //!
 
-use crate::traits::Integer;
+use crate::traits::{Integer, Zeroed};
 
 #[cfg(target_pointer_width = "32")]
 type UsizeTrue = u32;
@@ -16,6 +16,25 @@ pub const fn sizeof <T> () -> UsizeTrue {
     core::mem::size_of::<T>() as UsizeTrue
 }
 
+/// Value of C variable declared without initializer
+#[inline(always)]
+pub const fn zeroed <T: Zeroed> () -> T {
+    // SAFETY: guaranteed by `Zeroed`
+    unsafe { core::mem::zeroed() }
+}
+
+/// Array initialized by the first elements, the rest being zeroed
+#[inline(always)]
+pub const fn pad <T: Zeroed, const N: usize, const M: usize> (x: [T; M]) -> [T; N] {
+    let mut array: [T; N] = zeroed();
+    let mut i = 0;
+    while i < M {
+        array[i] = x[i];
+        i += 1
+    }
+    array
+}
+
 /// Realization of C `x++` (a = after)
 #[inline(always)]
 pub fn inca <T: Integer> (x: &mut T) -> T {
@@ -13,11 +13,22 @@
 //!     assert_eq!(add(7, 32), 39)
 //! }
 //! ```
+//!
+//! Directories searched by `#include <...>` and `#include "..."` are given after the file:
+//! ```rust,ignore
+//! qas!("main.c", include = ["include", "vendor/include"]);
+//! ```
+//!
+//! Variables at file scope and `static` variables of functions, which may be changed,
+//! become `static mut`, so that the functions using them are `unsafe` unless they are
+//! marked as safe; lowering them to atomics or thread-locals is not supported yet.
 
 #![no_std]
 
 extern crate qas_macro;
 
+// written before the lints, the safety is told in the docs of each item
+#[allow(clippy::missing_safety_doc, clippy::redundant_guards)]
 mod traits;
 pub mod builtin;
 
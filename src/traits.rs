@@ -91,6 +91,17 @@ pub trait Integer: Copy + Sized {
     fn sub_one_u8(&mut self, x: u8);
 }
 
+///
+/// Types, for which all-zero bytes are a valid value, i.e. every C type.
+///
+/// Used to give C locals declared without initializer a value
+///
+/// # Safety
+///
+/// Zeroed memory must be a valid value of the type
+///
+pub unsafe trait Zeroed: Copy {}
+
 unsafe impl CStringToRust for *const u8 {
     unsafe fn to_rust(&self) -> &str {
         let len = strlen(*self);
@@ -132,7 +143,8 @@ unsafe fn strlen(mut s: *const u8) -> usize {
     s as usize - start as usize
 }
 
-/// Helper macro to auto-implement trait `Integer` for all integers(and not only)
+/// Helper macro to auto-implement trait `Integer` for all signed integers,
+/// whose overflow is undefined in C, so that Rust checks it in debug builds
 macro_rules! impl_num {
     ($($ty:ident)*) => {
         $(impl Integer for $ty {
@@ -149,4 +161,56 @@ macro_rules! impl_num {
     };
 }
 
-impl_num!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
+impl_num!(i8 i16 i32 i64 i128 isize);
+
+/// Helper macro to auto-implement trait `Integer` for all unsigned integers, which wrap around
+macro_rules! impl_unsigned {
+    ($($ty:ident)*) => {
+        $(impl Integer for $ty {
+            #[inline(always)]
+            fn add_one_u8(&mut self, x: u8) {
+                *self = self.wrapping_add(x as Self);
+            }
+
+            #[inline(always)]
+            fn sub_one_u8(&mut self, x: u8) {
+                *self = self.wrapping_sub(x as Self);
+            }
+        })*
+    };
+}
+
+impl_unsigned!(u8 u16 u32 u64 u128 usize);
+
+/// Helper macro to implement trait `Integer` for raw pointers, which are moved by elements
+macro_rules! impl_ptr {
+    ($($ty:ty)*) => {
+        $(impl <T> Integer for $ty {
+            #[inline(always)]
+            fn add_one_u8(&mut self, x: u8) {
+                *self = self.wrapping_add(x as usize);
+            }
+
+            #[inline(always)]
+            fn sub_one_u8(&mut self, x: u8) {
+                *self = self.wrapping_sub(x as usize);
+            }
+        })*
+    };
+}
+
+impl_ptr!(*const T *mut T);
+
+/// Helper macro to implement trait `Zeroed` for all scalars
+macro_rules! impl_zeroed {
+    ($($ty:ident)*) => {
+        $(unsafe impl Zeroed for $ty {})*
+    };
+}
+
+impl_zeroed!(bool u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize f32 f64);
+
+unsafe impl Zeroed for () {}
+unsafe impl <T> Zeroed for *const T {}
+unsafe impl <T> Zeroed for *mut T {}
+unsafe impl <T: Zeroed, const N: usize> Zeroed for [T; N] {}
@@ -0,0 +1,19 @@
+use qas::prelude::*;
+
+qas!("tests/c/arrays.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    let mut a = [0; 5];
+    assert_eq!(unsafe { fill(a.as_mut_ptr(), 5) }, 16);
+    assert_eq!(a, [0, 1, 4, 9, 16]);
+    assert_eq!(unsafe { sum(a.as_ptr(), 5) }, 30);
+    assert_eq!(unsafe { squares() }, 14);
+    assert_eq!(grid(), 14);
+    assert_eq!(unsafe { initialized() }, 6 + 30 + 3 + 9);
+    assert_eq!(strings(), 104 + 99 + 99);
+    assert_eq!(unsafe { structs() }, 5 + 6 + 98 + 1 + 9);
+    assert_eq!(sizes(), 40 + 12 + 12 + 12);
+    assert_eq!(unevaluated(std::ptr::null_mut()), 4 + 4 + 1);
+}
@@ -0,0 +1,20 @@
+use qas::prelude::*;
+
+qas!("tests/c/assign.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(simple(1), 8);
+    assert_eq!(chained(4), 8);
+    assert_eq!(bits(0x47), 0xf);
+    assert_eq!(mods(19), 2);
+    assert_eq!(steps(3), 3);
+    assert_eq!(unsafe { through_ptr(3) }, 4);
+    assert_eq!(halved(5), 2.5);
+    assert_eq!(assigned_in_condition(3), 3);
+    assert_eq!(assigned_in_condition(0), -1);
+    assert_eq!(unsafe { copied() }, 51);
+    assert_eq!(indexed(), 3000 + 15 * 100 + 2 * 10 + 4);
+    assert_eq!(stepped(), 100 + 50 + 6);
+}
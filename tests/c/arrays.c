@@ -0,0 +1,84 @@
+#define N 4
+#define M 3
+
+enum { SLOTS = 5 };
+
+struct pair {
+    int values[2];
+    char name[M];
+};
+
+typedef int triple[3];
+
+long sum(const int *values, unsigned long len) {
+    long total = 0;
+    for (unsigned long i = 0; i < len; i++) {
+        total += values[i];
+    }
+    return total;
+}
+
+int fill(int out[], int len) {
+    for (int i = 0; i < len; i++) {
+        out[i] = i * i;
+    }
+    return out[len - 1];
+}
+
+long squares() {
+    int a[N];
+    fill(a, N);
+    return sum(a, N);
+}
+
+int grid() {
+    char buf[N][M];
+    int total = 0;
+    for (int i = 0; i < N; i++) {
+        for (int j = 0; j < M; j++) {
+            buf[i][j] = i + j;
+        }
+    }
+    for (int i = 0; i < N; i++) {
+        total += buf[i][M - 1];
+    }
+    return total;
+}
+
+long initialized() {
+    int a[] = {1, 2, 3};
+    int b[SLOTS] = {10, 20};
+    int m[2][2] = {{1, 2}, {3, 4}};
+    triple t = {7, 8, 9};
+    return sum(a, 3) + sum(b, SLOTS) + m[1][0] + t[2];
+}
+
+int strings() {
+    char hi[] = "hi";
+    char padded[8] = "abc";
+    char exact[3] = "abc";
+    int total = hi[0];
+    total += hi[2] + padded[7];
+    total += padded[2];
+    total += exact[2];
+    return total;
+}
+
+int structs() {
+    struct pair p = {{5, 6}, "ab"};
+    struct pair q = {{1}};
+    struct pair *r = &q;
+    r->values[1] = 9;
+    return p.values[0] + p.values[1] + p.name[1] + q.values[0] + q.values[1];
+}
+
+unsigned long sizes() {
+    int a[10];
+    struct pair p;
+    return sizeof(a) + sizeof p + sizeof(char[N][M]) + sizeof(triple);
+}
+
+unsigned long unevaluated(int *p) {
+    int n = 1;
+    return sizeof(n = 5) + sizeof *p + n;
+}
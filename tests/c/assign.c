@@ -0,0 +1,81 @@
+int simple(int a) {
+    int x;
+    x = a;
+    x += 2;
+    x *= 3;
+    x -= 1;
+    return x;
+}
+
+int chained(int a) {
+    int x, y;
+    x = y = a;
+    return x + y;
+}
+
+unsigned bits(unsigned a) {
+    a <<= 2;
+    a |= 1;
+    a &= 0xff;
+    a ^= 2;
+    a >>= 1;
+    return a;
+}
+
+int mods(int a) {
+    a %= 7;
+    a /= 2;
+    return a;
+}
+
+int steps(int n) {
+    int c, s = 0;
+    while ((c = n--) > 0) s++;
+    return s;
+}
+
+int through_ptr(int a) {
+    int v = 0;
+    int *p = &v;
+    *p = a;
+    *p += 1;
+    return v;
+}
+
+double halved(int a) {
+    double d;
+    d = a;
+    d /= 2;
+    return d;
+}
+
+int assigned_in_condition(int a) {
+    int x = 0;
+    if ((x = a)) return x;
+    return -1;
+}
+
+int copied(void) {
+    unsigned char src[6] = "hello";
+    unsigned char dst[6];
+    unsigned char *d = dst, *s = src;
+    int n = 0;
+    while ((*d++ = *s++)) n++;
+    return n * 10 + (dst[4] == 'o');
+}
+
+int indexed(void) {
+    unsigned char a[4] = {1, 2, 3, 4};
+    int k = 0;
+    a[k++] += 1;
+    a[k++] = a[3];
+    int v = (a[k++] *= 5);
+    return k * 1000 + v * 100 + a[0] * 10 + a[1];
+}
+
+__ATTR__(("overflow(checked)")) int stepped(void) {
+    int a[3] = {5, 6, 7};
+    int i = 0;
+    int v = a[i++]++;
+    return i * 100 + v * 10 + a[0];
+}
@@ -0,0 +1,43 @@
+typedef unsigned char byte;
+typedef long word;
+
+byte low(int x) {
+    return (byte)x;
+}
+
+int truncated() {
+    return (unsigned char)300;
+}
+
+int negative() {
+    return (signed char)255;
+}
+
+int round_down(double x) {
+    return (int)x + (int)2.75;
+}
+
+double halves(int x) {
+    return (double)x / 2;
+}
+
+long address_difference(int *p) {
+    void *raw = (void *)p;
+    char *bytes = (char *)raw;
+    return (long)(bytes + 4) - (long)p;
+}
+
+int first_byte(unsigned int *x) {
+    return *(unsigned char *)x;
+}
+
+int parens(int byte_count) {
+    int word_count = 3;
+    (void)word_count;
+    ((void)byte_count);
+    return (byte_count) * (word)2 + (word_count);
+}
+
+_Bool truth(int x) {
+    return (_Bool)x;
+}
@@ -0,0 +1,58 @@
+int letter() {
+    return 'a';
+}
+
+int escapes() {
+    return '\n' + '\t' + '\\' + '\'' + '\0' + '\?' + '"';
+}
+
+int numeric() {
+    return '\101' + '\x42' + '\7' + '\xff';
+}
+
+int controls() {
+    return '\a' * 1000000 + '\b' * 10000 + '\f' * 100 + '\v' + '\r' * 0;
+}
+
+int classify(int c) {
+    switch (c) {
+        case 'a':
+        case 'e':
+        case 'o':
+            return 1;
+        case '\n':
+            return 2;
+        default:
+            return 0;
+    }
+}
+
+int count(const char *s, char c) {
+    int n = 0;
+    while (*s != '\0') {
+        if (*s == c) {
+            n += 1;
+        }
+        s++;
+    }
+    return n;
+}
+
+int quotes() {
+    return count("say \"hi\"\n", '"') * 10 + count("a\tb\\c\\", '\\');
+}
+
+int bytes() {
+    char s[] = "\101\x42\0C\xff";
+    int total = sizeof s;
+    for (int i = 0; i < 5; i++) {
+        total += s[i];
+    }
+    return total;
+}
+
+int octal_digits() {
+    char s[] = "\1234";
+    int first = s[0];
+    return first * 100 + s[1];
+}
@@ -0,0 +1,64 @@
+#define LEVEL 3
+#define TWICE_LEVEL LEVEL * 2
+#define EMPTY
+
+#if __STDC_VERSION__ >= 199901L
+int modern() { return 1; }
+#else
+int modern() { return 0; }
+#endif
+
+#if LEVEL > 5
+int level() { return 5; }
+#elif LEVEL > 2
+int level() { return 2; }
+#elif LEVEL > 1
+int level() { return 1; }
+#else
+int level() { return 0; }
+#endif
+
+#if TWICE_LEVEL == 6 && defined(EMPTY) && defined LEVEL && !defined(UNKNOWN)
+int expanded() { return 1; }
+#endif
+
+#if UNKNOWN == 0 && !UNKNOWN
+int unknown() { return 1; }
+#endif
+
+#if -1 < 0u
+int signedness() { return 0; }
+#else
+int signedness() { return 1; }
+#endif
+
+#if 0 && 1 / 0
+#elif (0xff >> 4) == 15 && (1 << 62) > 0 && ~0 == -1 && 7 % 4 == 3 && (2 ? 5 : 1 / 0) == 5 && 'A' == 65
+int operators() { return 1; }
+#endif
+
+#ifdef LEVEL
+#   if LEVEL == 3
+#       ifndef NOT_DEFINED
+int nested() { return 3; }
+#       else
+#           error never
+#       endif
+#   elif 1 / 0
+#   endif
+#endif
+
+#if 0
+#define SKIPPED 1
+#if 1
+int broken( {
+#endif
+#endif
+
+#ifdef SKIPPED
+int skipped() { return 1; }
+#else
+int skipped() { return 0; }
+#endif
+
+int line() { return __LINE__; }
@@ -0,0 +1,67 @@
+int sign(int x) {
+    if (x > 0) return 1;
+    else if (x < 0) {
+        return -1;
+    }
+    return 0;
+}
+
+int is_null(const char *p) {
+    if (p) {
+        return 0;
+    }
+    return 1;
+}
+
+int is_nonzero(double d) {
+    if (d) return 1;
+    else return 0;
+}
+
+int truthy(int x) {
+    if (x) return 1;
+    return 0;
+}
+
+int dangling(int a, int b) {
+    if (a)
+        if (b) return 1;
+        else return 2;
+    return 3;
+}
+
+int chain(unsigned x) {
+    if (x == 1) {
+        return 10;
+    } else if (x == 2) {
+        return 20;
+    } else if (x == 3) return 30;
+    else {
+        int y = x;
+        return y;
+    }
+}
+
+void early(int x) {
+    if (x) return;
+    return;
+}
+
+int both(int a, int b) {
+    if (a && b) {
+        return 1;
+    }
+    return 0;
+}
+
+int both_set(const int *p, const int *q) {
+    if (p && q) return *p + *q;
+    return 0;
+}
+
+int in_range(int a, int b) {
+    if (a > 0 && b > 0 && a < b) {
+        return 1;
+    }
+    return 0;
+}
@@ -0,0 +1,109 @@
+int promoted() {
+    unsigned char a = 200, b = 100;
+    return a + b;
+}
+
+int promoted_short() {
+    short a = 30000, b = 30000;
+    return a + b;
+}
+
+int negated() {
+    unsigned char c = 1;
+    return -c;
+}
+
+int inverted() {
+    unsigned char c = 0;
+    return ~c;
+}
+
+/* size of the common type of each pair, 8 for `long`, 4 for `int` */
+long ranks() {
+    char c = 0;
+    short s = 0;
+    int i = 0;
+    unsigned int u = 0;
+    long l = 0;
+    unsigned long ul = 0;
+    return sizeof(c + c) * 100000 + sizeof(c + s) * 10000 + sizeof(s + i) * 1000
+        + sizeof(i + u) * 100 + sizeof(u + l) * 10 + sizeof(l + ul);
+}
+
+/* which of the pairs have unsigned common type */
+int signedness() {
+    signed char c = -1;
+    unsigned short us = 1;
+    int i = -1;
+    unsigned int u = 1;
+    long l = -1;
+    unsigned long ul = 1;
+    return (c < us) * 1000 + (i < u) * 100 + (l < u) * 10 + (l < ul);
+}
+
+int literal_signedness() {
+    return (-1 > 0u) * 10 + (-1 > 0);
+}
+
+double mixed() {
+    int i = 1;
+    float f = 0.5f;
+    return i / 2 + f + 3 / 2.0;
+}
+
+int shifts() {
+    unsigned char c = 1;
+    long amount = 9;
+    return c << amount;
+}
+
+long choose(int flag) {
+    unsigned char small = 255;
+    long big = -1;
+    return flag ? small : big;
+}
+
+int bits() {
+    int x = -8;
+    return (x & 12) + (x | 1) + (x ^ 3);
+}
+
+/* size of the common type of each pair, times 10, plus whether it is unsigned */
+#define PAIR(name, A, B) int name(void) { A a = 0; B b = 0; return sizeof(a + b) * 10 + (a + b - 1 > 0); }
+
+PAIR(sc_sc, signed char, signed char)
+PAIR(sc_uc, signed char, unsigned char)
+PAIR(sc_ss, signed char, short)
+PAIR(sc_us, signed char, unsigned short)
+PAIR(sc_si, signed char, int)
+PAIR(sc_ui, signed char, unsigned int)
+PAIR(sc_sl, signed char, long)
+PAIR(sc_ul, signed char, unsigned long)
+PAIR(uc_uc, unsigned char, unsigned char)
+PAIR(uc_ss, unsigned char, short)
+PAIR(uc_us, unsigned char, unsigned short)
+PAIR(uc_si, unsigned char, int)
+PAIR(uc_ui, unsigned char, unsigned int)
+PAIR(uc_sl, unsigned char, long)
+PAIR(uc_ul, unsigned char, unsigned long)
+PAIR(ss_ss, short, short)
+PAIR(ss_us, short, unsigned short)
+PAIR(ss_si, short, int)
+PAIR(ss_ui, short, unsigned int)
+PAIR(ss_sl, short, long)
+PAIR(ss_ul, short, unsigned long)
+PAIR(us_us, unsigned short, unsigned short)
+PAIR(us_si, unsigned short, int)
+PAIR(us_ui, unsigned short, unsigned int)
+PAIR(us_sl, unsigned short, long)
+PAIR(us_ul, unsigned short, unsigned long)
+PAIR(si_si, int, int)
+PAIR(si_ui, int, unsigned int)
+PAIR(si_sl, int, long)
+PAIR(si_ul, int, unsigned long)
+PAIR(ui_ui, unsigned int, unsigned int)
+PAIR(ui_sl, unsigned int, long)
+PAIR(ui_ul, unsigned int, unsigned long)
+PAIR(sl_sl, long, long)
+PAIR(sl_ul, long, unsigned long)
+PAIR(ul_ul, unsigned long, unsigned long)
@@ -0,0 +1,35 @@
+enum color { RED, GREEN = 5, BLUE, LAST = BLUE * 2 + 1 };
+
+enum { ZERO, ONE, };
+
+int color_value(enum color c) {
+    return c;
+}
+
+enum color next_color(enum color c) {
+    if (c == RED) {
+        return GREEN;
+    }
+    return c + 1;
+}
+
+int classify(int x) {
+    switch (x) {
+        case RED:
+            return 10;
+        case GREEN:
+        case BLUE:
+            return 20;
+        case LAST:
+            return 30;
+        default:
+            return -1;
+    }
+}
+
+int local_enum() {
+    enum dir { UP = -1, DOWN = 1 };
+    enum dir d = UP;
+    d += DOWN * 3;
+    return d + ONE;
+}
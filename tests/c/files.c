@@ -0,0 +1,24 @@
+enum color { RED = 1, GREEN };
+
+struct pa {
+    int a;
+    int b;
+};
+
+struct type {
+    int x;
+};
+
+int pa(void) {
+    struct pa p = {1, 2};
+    return p.a + p.b;
+}
+
+int typed(void) {
+    struct type t = {7};
+    return t.x;
+}
+
+int green(void) {
+    return GREEN;
+}
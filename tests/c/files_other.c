@@ -0,0 +1,16 @@
+enum shade { DARK = 5, LIGHT };
+
+struct pa {
+    int c;
+    int d;
+    int e;
+};
+
+int pa(void) {
+    struct pa p = {3, 4, 5};
+    return p.c * p.d * p.e;
+}
+
+int light(void) {
+    return LIGHT;
+}
@@ -0,0 +1,36 @@
+int permissions() {
+    return 0755;
+}
+
+int zero() {
+    return 0 + 00;
+}
+
+double exponents() {
+    return 1e3 + 2.5E-3 + 1.e1 + 5E+0;
+}
+
+double leading_dot() {
+    return .5 + .25e1;
+}
+
+double hex() {
+    return 0x1.8p3 + 0X10P-4 + 0x.8p1;
+}
+
+long sizes() {
+    return sizeof(1.0) * 100 + sizeof(1.0f) * 10 + sizeof(2.F) + sizeof(1.5L) * 1000;
+}
+
+float single() {
+    return 1.25f * 2;
+}
+
+int truncated() {
+    int x = 2.9e1;
+    return x;
+}
+
+long long big() {
+    return 1e12;
+}
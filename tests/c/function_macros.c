@@ -0,0 +1,81 @@
+#define MAX(a, b) ((a) > (b) ? (a) : (b))
+#define SQUARE(x) ((x) * (x))
+#define ADD3(a, b, c) ((a) + (b) + (c))
+#define ZERO() 0
+#define ID(x) x
+#define TWICE(x) ADD3(x, x, 0)
+#define PICK MAX
+#define CALL(f, x) f(x)
+
+int max(int x, int y) {
+    return MAX(x, y);
+}
+
+int nested(int x) {
+    return SQUARE(MAX(x, (2 + 1)));
+}
+
+int multiline(int x) {
+    return ADD3(x,
+                x * 2,
+                SQUARE(x));
+}
+
+int rescanned(int x) {
+    return TWICE(x) + ZERO() + ID(ID(x));
+}
+
+int indirect(int x) {
+    return PICK(x, 10);
+}
+
+int count = 4;
+#define count (count + 1)
+
+int recursive(void) {
+    return count;
+}
+
+int applied(int x) {
+    return CALL(SQUARE, x + 1);
+}
+
+int untouched(int MAX) {
+    int SQUARE = MAX + 1;
+    return SQUARE;
+}
+
+unsigned char quoted() {
+    const unsigned char *s = "MAX(1, 2)";
+    return s[0];
+}
+
+int line() {
+    return __LINE__;
+}
+
+#define f(x) (x*2)
+#define h() f
+
+int followed(void) {
+    return h()(3) + ID(f)(4);
+}
+
+int identity_count(void) {
+    return ID(count);
+}
+
+int z[1][1][1] = {{{7}}};
+#define z z[0]
+
+int painted(void) {
+    return ID(ID(z))[0][0];
+}
+
+#undef z
+#define CALLER f
+#define OPEN CALLER(~
+
+int opened(void) {
+    return OPEN 5);
+}
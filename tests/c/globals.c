@@ -0,0 +1,73 @@
+extern int shared;
+
+int counter = 0;
+static int hidden;
+const int limit = 3;
+const int primes[4] = {2, 3, 5, 7};
+int table[] = {10, 20, 30};
+unsigned char name[8] = "qas";
+const char *greeting = "hi";
+
+struct point {
+    int x;
+    int y;
+};
+
+struct point origin = {1, 2};
+
+int next(void) {
+    counter++;
+    hidden += 2;
+    return counter;
+}
+
+int total(void) {
+    return counter + hidden;
+}
+
+int capped(int x) {
+    if (x > limit) {
+        return limit;
+    }
+    return x;
+}
+
+int prime(int i) {
+    return primes[i];
+}
+
+int sum_table(void) {
+    int s = 0;
+    for (int i = 0; i < 3; i++) {
+        s += table[i];
+    }
+    return s;
+}
+
+int through_pointer(void) {
+    int *p = &table[1];
+    *p = 25;
+    int *q = table;
+    return q[1] + q[0];
+}
+
+int moved(void) {
+    origin.x += 10;
+    struct point *p = &origin;
+    return p->x + origin.y;
+}
+
+unsigned char letter(int i) {
+    return name[i];
+}
+
+int shadowed(int counter) {
+    return counter * 2;
+}
+
+__ATTR__(("safe")) int bump_shared(void) {
+    shared = shared + 1;
+    return shared;
+}
+
+int shared = 40;
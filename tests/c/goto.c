@@ -0,0 +1,85 @@
+int cleanup(int x) {
+    int r = 0;
+    if (x < 0) goto fail;
+    r++;
+    if (x == 0) goto done;
+    r++;
+done:
+    return r;
+fail:
+    return -1;
+}
+
+int retry(int n) {
+    int tries = 0;
+again:
+    tries++;
+    if (tries < n) goto again;
+    return tries;
+}
+
+int across(int x) {
+    int y = x;
+    if (x) goto skip;
+    y++;
+skip:
+    return y;
+}
+
+int found(int n) {
+    for (int i = 0; i < 100; i++) {
+        if (i * i >= n) goto yes;
+    }
+    return -1;
+yes:
+    return 1;
+}
+
+int both_ways(int n) {
+    int c = 0;
+    goto middle;
+top:
+    c++;
+middle:
+    c++;
+    if (c < n) goto top;
+    {
+        int k = c;
+        if (k > 100) goto out;
+        k++;
+    out:
+        ;
+    }
+    return c;
+}
+
+void nothing(int n) {
+    goto end;
+    n++;
+end:
+    return;
+}
+
+int shadowed(int x) {
+    int n = 0;
+    {
+        int z = x;
+        int x = 2;
+    again:
+        n += x;
+        if (n < 5) goto again;
+        return z;
+    }
+}
+
+int nested_tail(int n) {
+    int y = 0;
+    {
+        int c = 0;
+    again:
+        c++;
+        if (c < n) goto again;
+        y = c;
+        return y;
+    }
+}
@@ -0,0 +1,15 @@
+#pragma once
+
+#define SIZE 4
+
+int size() {
+    return SIZE;
+}
+
+int config_line() {
+    return __LINE__;
+}
+
+const char *config_file() {
+    return __FILE__;
+}
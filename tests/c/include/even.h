@@ -0,0 +1,10 @@
+#ifndef EVEN_H
+#define EVEN_H
+
+#include "odd.h"
+
+int is_even(unsigned n) {
+    return n == 0 ? 1 : is_odd(n - 1);
+}
+
+#endif
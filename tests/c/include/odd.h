@@ -0,0 +1,10 @@
+#ifndef ODD_H
+#define ODD_H
+
+#include "even.h"
+
+int is_odd(unsigned n) {
+    return n == 0 ? 0 : is_even(n - 1);
+}
+
+#endif
@@ -0,0 +1,5 @@
+#include "square.h"
+
+int cube(int x) {
+    return square(x) * x;
+}
@@ -0,0 +1,5 @@
+#pragma once
+
+int square(int x) {
+    return x * x;
+}
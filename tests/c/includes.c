@@ -0,0 +1,23 @@
+#include <config.h>
+#include <config.h>
+#include "config.h"
+#include <util/math.h>
+#include "include/util/square.h"
+#include "include/even.h"
+
+int area() {
+    return square(SIZE);
+}
+
+int volume() {
+    return cube(SIZE);
+}
+
+int line() {
+    return __LINE__;
+}
+
+#line 1 "renamed \"file\".c"
+const char *renamed() {
+    return __FILE__;
+}
@@ -0,0 +1,41 @@
+int big() {
+    return 300 + 400;
+}
+
+int minus_one() {
+    return -1;
+}
+
+unsigned int all_ones() {
+    return -1;
+}
+
+long sizes() {
+    return sizeof(1) * 1000 + sizeof(2147483648) * 100 + sizeof(0x7fffffff) * 10 + sizeof(0xffffffff);
+}
+
+long suffixes() {
+    return sizeof(1u) + sizeof(1l) + sizeof(1L) + sizeof(1ll) + sizeof(1LL)
+        + sizeof(1ul) + sizeof(1lu) + sizeof(1ULL) + sizeof(1llu) + sizeof(1LLU);
+}
+
+int is_unsigned() {
+    return (0xffffffff > 0) + (-1 < 0) * 100 + (0xffffffffu == 4294967295) * 1000;
+}
+
+long long wide() {
+    return 4000000000 * 2;
+}
+
+unsigned char truncated() {
+    unsigned char c = 300;
+    return c;
+}
+
+int negative_hex() {
+    return -0x10;
+}
+
+long long smallest() {
+    return -9223372036854775807LL - 1;
+}
@@ -0,0 +1,45 @@
+int add_one(int a) {
+    int x = a + 1;
+    return x;
+}
+
+int first_of_three(int a, int b) {
+    int x = a, y = b, z;
+    return x;
+}
+
+int shadowed(int a) {
+    int x = a;
+    {
+        int x = 10;
+        x++;
+    }
+    return x;
+}
+
+int incremented(int a) {
+    int x = a;
+    ++x;
+    x++;
+    return x;
+}
+
+int tripled(int a) {
+    const int k = 3;
+    return a * k;
+}
+
+long widened(int a) {
+    long x = a;
+    return x;
+}
+
+const char *greeting() {
+    const char *s = "Hello", *unused;
+    return s;
+}
+
+int uninit() {
+    int x;
+    return x;
+}
@@ -0,0 +1,101 @@
+int count_to(int n) {
+    int c = 0;
+    for (int i = 0; i < n; i++) c++;
+    return c;
+}
+
+int count_inclusive(int n) {
+    int c = 0;
+    for (int i = 1; i <= n; ++i) {
+        c++;
+    }
+    return c;
+}
+
+int count_even(int n) {
+    int c = 0;
+    for (int i = 0; i < n; i++) {
+        if (i % 2) continue;
+        c++;
+    }
+    return c;
+}
+
+int skip_with_step(int n) {
+    int c = 0;
+    for (int i = 0; i < n; ++i) {
+        if (i == 2) {
+            i++;
+            continue;
+        }
+        c++;
+    }
+    return c;
+}
+
+int countdown(int n) {
+    int c = 0;
+    while (n) {
+        n--;
+        c++;
+    }
+    return c;
+}
+
+int at_least_once(int n) {
+    int c = 0;
+    do {
+        c++;
+    } while (c < n);
+    return c;
+}
+
+int do_continue(int n) {
+    int c = 0, i = 0;
+    do {
+        i++;
+        if (i > 3) continue;
+        c++;
+    } while (i < n);
+    return c;
+}
+
+int forever(int n) {
+    int c = 0;
+    for (;;) {
+        if (c == n) break;
+        c++;
+    }
+    return c;
+}
+
+int nested(int n) {
+    int c = 0;
+    for (int i = 0; i < n; i++) {
+        int j = 0;
+        while (1) {
+            if (j == i) break;
+            j++;
+            c++;
+        }
+    }
+    return c;
+}
+
+int below_unsigned(unsigned n) {
+    int c = 0;
+    for (int i = 0; i < n; i++) {
+        c++;
+        if (c == 3) break;
+    }
+    return c;
+}
+
+int small_counter(void) {
+    int c = 0;
+    for (unsigned char i = 250; i < 300; i++) {
+        c++;
+        if (c == 10) break;
+    }
+    return c;
+}
@@ -0,0 +1,55 @@
+unsigned int below_zero() {
+    unsigned int x = 0;
+    return x - 1;
+}
+
+unsigned int product(unsigned int a, unsigned int b) {
+    return a * b;
+}
+
+unsigned long shifted(unsigned long x, int amount) {
+    return x << amount;
+}
+
+unsigned char steps() {
+    unsigned char c = 255;
+    c++;
+    ++c;
+    c -= 3;
+    return c;
+}
+
+unsigned int negated(unsigned int x) {
+    return -x;
+}
+
+/*
+ * signed overflow is undefined in C, so each function chooses what it does
+ */
+__ATTR__(("overflow(wrap)")) int wrapped(int a, int b) {
+    int x = a + b;
+    x++;
+    return -x * 1;
+}
+
+__ATTR__(("overflow(checked)")) int checked(int a, int b) {
+    int sum = a;
+    sum += b;
+    return sum;
+}
+
+__ATTR__(("overflow(checked)")) int checked_step(int x) {
+    x++;
+    ++x;
+    return x + 1 + (x + 2);
+}
+
+__ATTR__(("overflow(trap)")) int trapped(int a, int b) {
+    return a - b;
+}
+
+#include "include/three.h"
+
+__ATTR__(("overflow(checked)")) int after_include(int a) {
+    return a + three();
+}
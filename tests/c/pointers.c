@@ -0,0 +1,49 @@
+struct span {
+    int *begin;
+    int *end;
+};
+
+long length(struct span s) {
+    return s.end - s.begin;
+}
+
+int total(const int *begin, const int *end) {
+    int sum = 0;
+    while (begin < end) {
+        sum += *begin++;
+    }
+    return sum;
+}
+
+int last(int *values, int len) {
+    int *p = values + len;
+    --p;
+    return *p;
+}
+
+long walk() {
+    int a[6];
+    int i = 0;
+    for (int *p = a; p != a + 6; p++) {
+        *p = i;
+        i += 1;
+    }
+
+    int *q = 2 + a;
+    q += 3;
+    q -= 1;
+    long before = q - a;
+
+    int *r = q--;
+    unsigned int back = 2;
+    r = r - back;
+    return before * 100 + (r - a) * 10 + *q;
+}
+
+int nulls(int *p) {
+    if (p == 0) {
+        return -1;
+    }
+    int *none = 0;
+    return none != p;
+}
@@ -0,0 +1,82 @@
+int square(int);
+int twice(int x);
+int nothing(void);
+__ATTR__(("safe")) int is_odd(unsigned n);
+
+int sum_of_squares(int a, int b) {
+    return square(a) + square(b);
+}
+
+int square(int x) {
+    return x * x;
+}
+
+int twice(int x) {
+    return x + x;
+}
+
+int nothing(void) {
+    return 0;
+}
+
+int forward(int x) {
+    return later(x) + nothing();
+}
+
+int later(int x) {
+    return twice(x) + 1;
+}
+
+int is_even(unsigned n) {
+    if (n == 0) {
+        return 1;
+    }
+    return is_odd(n - 1);
+}
+
+int is_odd(unsigned n) {
+    if (n == 0) {
+        return 0;
+    }
+    return is_even(n - 1);
+}
+
+int local(int x) {
+    int negate(int);
+    return negate(x);
+}
+
+int negate(int x) {
+    return -x;
+}
+
+__ATTR__(("safe")) int guarded(int x) {
+    return incremented(&x);
+}
+
+int incremented(int *p) {
+    return *p + 1;
+}
+
+int tripled(int);
+__ATTR__(("safe")) int doubled(int x);
+
+int external(int x) {
+    return tripled(x) + doubled(x);
+}
+
+int product();
+
+int early_product(void) {
+    return product(3, 4);
+}
+
+int product(int a, int b) {
+    return a * b;
+}
+
+int decrement();
+
+int decrement(int x) {
+    return x - 1;
+}
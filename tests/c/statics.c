@@ -0,0 +1,51 @@
+int calls(void) {
+    static int count = 0;
+    count++;
+    return count;
+}
+
+int other_calls(void) {
+    static int count;
+    count += 10;
+    return count;
+}
+
+int sequence(void) {
+    static int values[3] = {5, 6, 7};
+    static int i = 0;
+    int x = values[i % 3];
+    i++;
+    return x;
+}
+
+int scoped(int reset) {
+    int total = 0;
+    {
+        static int seen = 100;
+        if (reset) {
+            seen = 0;
+        }
+        seen++;
+        total = seen;
+    }
+    return total;
+}
+
+int *counter_address(void) {
+    static int counter;
+    counter++;
+    return &counter;
+}
+
+__ATTR__(("safe")) int ticks(void) {
+    static const int step = 2;
+    static unsigned int ticks = 0;
+    ticks += step;
+    return ticks;
+}
+
+int calls_count = 7;
+
+int other_calls_count(void) {
+    return calls_count + 1;
+}
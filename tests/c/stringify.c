@@ -0,0 +1,49 @@
+#define STR(x) #x
+#define XSTR(x) STR(x)
+#define VALUE 42
+#define CAT(a, b) a ## b
+#define XCAT(a, b) CAT(a, b)
+#define DEFINE_GETTER(name) int get_ ## name(void) { return name; }
+#define PREFIXED(x) prefix_ ## x ## _end
+#define OBJECT one ## _two
+
+int width = 7;
+int height = 3;
+int prefix_mid_end = 11;
+int one_two = 12;
+int VALUE1 = 13;
+
+DEFINE_GETTER(width)
+DEFINE_GETTER(height)
+
+unsigned char at(const unsigned char *s, int i) {
+    return s[i];
+}
+
+unsigned char stringified(int i) {
+    return at(STR(a  +   "b\n"), i);
+}
+
+unsigned char expanded(int i) {
+    return at(XSTR(VALUE), i);
+}
+
+unsigned char unexpanded(int i) {
+    return at(STR(VALUE), i);
+}
+
+unsigned char empty() {
+    return at(STR(), 0);
+}
+
+int pasted() {
+    return CAT(wid, th) + CAT(, height) + CAT(VALUE, 1) + XCAT(VALUE, 0);
+}
+
+int twice() {
+    return PREFIXED(mid) + OBJECT;
+}
+
+int number() {
+    return CAT(1, 5) + CAT(0x, 1f);
+}
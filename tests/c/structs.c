@@ -0,0 +1,90 @@
+struct point {
+    int x;
+    int y;
+};
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+typedef struct {
+    unsigned char tag;
+    struct point at;
+    double weight;
+} Item;
+
+struct outer {
+    char c;
+    struct inner {
+        long l;
+        char d;
+    } in;
+    struct {
+        short s;
+        int i;
+    };
+};
+
+struct point make_point(int x, int y) {
+    struct point p;
+    p.x = x;
+    p.y = y;
+    return p;
+}
+
+int manhattan(struct point p) {
+    return p.x + p.y;
+}
+
+int sum_list(struct node *head) {
+    int sum = 0;
+    while (head) {
+        sum += head->value;
+        head = head->next;
+    }
+    return sum;
+}
+
+int linked(int a, int b, int c) {
+    struct node n1, n2, n3;
+    n1.value = a;
+    n1.next = &n2;
+    n2.value = b;
+    n2.next = &n3;
+    n3.value = c;
+    return sum_list(&n1) + n1.next->next->value;
+}
+
+int copied() {
+    struct point a = make_point(1, 2);
+    struct point b;
+    b = a;
+    b.x = 10;
+    return a.x + b.x + make_point(3, 4).y;
+}
+
+int item() {
+    Item it;
+    it.tag = 7;
+    it.at = make_point(3, 4);
+    it.at.y += 1;
+    return manhattan(it.at) + it.tag;
+}
+
+long nested() {
+    struct outer o;
+    struct inner *inner = &o.in;
+    o.s = 3;
+    o.i = 4;
+    inner->l = 5;
+    return o.s + o.i + o.in.l;
+}
+
+unsigned long size_item() {
+    return sizeof(Item);
+}
+
+unsigned long size_outer() {
+    return sizeof(struct outer);
+}
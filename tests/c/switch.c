@@ -0,0 +1,78 @@
+int plain(int x) {
+    int r = 0;
+    switch (x) {
+        case 1:
+            r++;
+            break;
+        case 2:
+        case 3:
+            r--;
+            break;
+        case -1:
+            return -10;
+        default:
+            r++;
+            r++;
+            break;
+        case 4 + 1:
+            return 50;
+    }
+    return r;
+}
+
+int fallthrough(int x) {
+    int r = 0;
+    switch (x) {
+        case 0:
+            r++;
+        case 1:
+            r++;
+        case 2:
+            r++;
+            break;
+        case 3:
+            r--;
+    }
+    return r;
+}
+
+int default_in_middle(unsigned x) {
+    int r = 0;
+    switch (x) {
+        case 1:
+            r++;
+        default:
+            r++;
+            r++;
+        case 2:
+            r++;
+    }
+    return r;
+}
+
+int inner_break(int x, int y) {
+    int r = 0;
+    switch (x) {
+        case 1:
+            if (y) break;
+            r++;
+            break;
+        default:
+            r--;
+    }
+    return r;
+}
+
+int skip_in_loop(int n) {
+    int r = 0;
+    for (int i = 0; i < n; i++) {
+        switch (i % 3) {
+            case 0:
+                continue;
+            case (1 << 1) - 1:
+                r++;
+        }
+        r++;
+    }
+    return r;
+}
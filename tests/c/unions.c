@@ -0,0 +1,84 @@
+union bits {
+    float f;
+    unsigned int u;
+};
+
+union wide {
+    char c;
+    double d;
+    int i;
+};
+
+struct value {
+    int kind;
+    union {
+        long i;
+        double f;
+    } as;
+};
+
+struct number {
+    _Bool is_float;
+    union {
+        long l;
+        double d;
+    };
+};
+
+unsigned int float_bits(float f) {
+    union bits b;
+    b.f = f;
+    return b.u;
+}
+
+__ATTR__(("safe")) unsigned int low_byte(unsigned int x) {
+    union bits b;
+    b.u = x;
+    b.u += 1;
+    return b.u & 255;
+}
+
+struct value make_int(long i) {
+    struct value v;
+    v.kind = 0;
+    v.as.i = i;
+    return v;
+}
+
+struct value make_float(double f) {
+    struct value v;
+    v.kind = 1;
+    v.as.f = f;
+    return v;
+}
+
+__ATTR__(("safe")) double as_double(struct value v) {
+    if (v.kind == 0) {
+        return v.as.i;
+    }
+    return v.as.f;
+}
+
+static __ATTR__(("safe")) double number(struct number *n) {
+    if (n->is_float) {
+        return n->d;
+    }
+    return n->l;
+}
+
+void set_double(struct number *n, double d) {
+    n->is_float = 1;
+    n->d = d;
+}
+
+double read_double(struct number n) {
+    return n.d;
+}
+
+unsigned long size_wide() {
+    return sizeof(union wide);
+}
+
+unsigned long size_value() {
+    return sizeof(struct value);
+}
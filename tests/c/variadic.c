@@ -0,0 +1,59 @@
+#define FIRST(x, ...) x
+#define REST(x, ...) __VA_ARGS__
+#define CALL(f, ...) f(__VA_ARGS__)
+#define COUNT(...) count(0 __VA_OPT__(, __VA_ARGS__))
+#define GNU(f, ...) f(0, ## __VA_ARGS__)
+#define STR(...) #__VA_ARGS__
+#define ALL(...) add3(__VA_ARGS__)
+#define F(...) one(0 __VA_OPT__(,) __VA_ARGS__)
+#define SUM(...) (0 __VA_OPT__(+) __VA_ARGS__)
+#define EMP
+
+int add3(int a, int b, int c) {
+    return a + b + c;
+}
+
+int count(int a, int b, int c) {
+    return a + b + c;
+}
+
+int one(int a) {
+    return a + 100;
+}
+
+int two(int a, int b) {
+    return a + b + 200;
+}
+
+int none(void) {
+    return 7;
+}
+
+int first() {
+    return FIRST(1, 2, 3) + FIRST(4);
+}
+
+int rest() {
+    return add3(REST(0, 1, 2, 3));
+}
+
+int call() {
+    return CALL(add3, 1, 2, 3) + CALL(none);
+}
+
+int opt() {
+    return COUNT(1, 2) + ALL(1, 2, 3);
+}
+
+int gnu() {
+    return GNU(one) + GNU(two, 5);
+}
+
+unsigned char stringified(int i) {
+    const unsigned char *s = STR(a,b,  c);
+    return s[i];
+}
+
+int empty_opt() {
+    return F(EMP) + SUM(EMP) + SUM(EMP 5);
+}
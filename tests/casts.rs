@@ -0,0 +1,20 @@
+use qas::prelude::*;
+
+qas!("tests/c/casts.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(low(0x1234), 0x34);
+    assert_eq!(truncated(), 44);
+    assert_eq!(negative(), -1);
+    assert_eq!(round_down(3.9), 5);
+    assert_eq!(halves(5), 2.5);
+    let mut x = 7;
+    assert_eq!(unsafe { address_difference(&mut x) }, 4);
+    let mut y: u32 = 0x0102_0304;
+    assert_eq!(unsafe { first_byte(&mut y) }, 4);
+    assert_eq!(parens(5), 13);
+    assert!(truth(-3));
+    assert!(!truth(0));
+}
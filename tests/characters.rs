@@ -0,0 +1,18 @@
+use qas::prelude::*;
+
+qas!("tests/c/characters.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(letter(), 97);
+    assert_eq!(escapes(), 10 + 9 + 92 + 39 + 63 + 34);
+    assert_eq!(numeric(), 65 + 66 + 7 + 255);
+    assert_eq!(controls(), 7080000 + 1200 + 11);
+    assert_eq!(classify('e' as i32), 1);
+    assert_eq!(classify('\n' as i32), 2);
+    assert_eq!(classify('x' as i32), 0);
+    assert_eq!(unsafe { quotes() }, 22);
+    assert_eq!(bytes(), 65 + 66 + 67 + 255 + 6);
+    assert_eq!(octal_digits(), 8300 + 52);
+}
@@ -0,0 +1,17 @@
+use qas::prelude::*;
+
+qas!("tests/c/conditionals.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(modern(), 1);
+    assert_eq!(level(), 2);
+    assert_eq!(expanded(), 1);
+    assert_eq!(unknown(), 1);
+    assert_eq!(signedness(), 1);
+    assert_eq!(operators(), 1);
+    assert_eq!(nested(), 3);
+    assert_eq!(skipped(), 0);
+    assert_eq!(line(), 64);
+}
@@ -0,0 +1,35 @@
+use qas::prelude::*;
+
+qas!("tests/c/conditions.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(sign(5), 1);
+    assert_eq!(sign(-5), -1);
+    assert_eq!(sign(0), 0);
+    assert_eq!(is_null(core::ptr::null()), 1);
+    assert_eq!(is_null("x\0".as_ptr()), 0);
+    assert_eq!(is_nonzero(0.0), 0);
+    assert_eq!(is_nonzero(0.5), 1);
+    assert_eq!(truthy(0), 0);
+    assert_eq!(truthy(-7), 1);
+    assert_eq!(dangling(1, 1), 1);
+    assert_eq!(dangling(1, 0), 2);
+    assert_eq!(dangling(0, 1), 3);
+    assert_eq!(chain(1), 10);
+    assert_eq!(chain(2), 20);
+    assert_eq!(chain(3), 30);
+    assert_eq!(chain(4), 4);
+    assert_eq!(both(1, 2), 1);
+    assert_eq!(both(1, 0), 0);
+    assert_eq!(both(0, 2), 0);
+    let (x, y) = (3, 4);
+    assert_eq!(unsafe { both_set(&x, &y) }, 7);
+    assert_eq!(unsafe { both_set(&x, core::ptr::null()) }, 0);
+    assert_eq!(in_range(1, 2), 1);
+    assert_eq!(in_range(2, 1), 0);
+    assert_eq!(in_range(-1, 2), 0);
+    early(1);
+    early(0)
+}
@@ -0,0 +1,61 @@
+use qas::prelude::*;
+
+qas!("tests/c/conversions.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(promoted(), 300);
+    assert_eq!(promoted_short(), 60000);
+    assert_eq!(negated(), -1);
+    assert_eq!(inverted(), -1);
+    assert_eq!(ranks(), 400000 + 40000 + 4000 + 400 + 80 + 8);
+    assert_eq!(signedness(), 1000 + 10);
+    assert_eq!(literal_signedness(), 10);
+    assert_eq!(mixed(), 2.0);
+    assert_eq!(shifts(), 512);
+    assert_eq!(choose(1), 255);
+    assert_eq!(choose(0), -1);
+    assert_eq!(bits(), 8 - 7 + -5);
+
+    for (i, &(common, size, unsigned)) in [
+        (sc_sc(), 4, false),
+        (sc_uc(), 4, false),
+        (sc_ss(), 4, false),
+        (sc_us(), 4, false),
+        (sc_si(), 4, false),
+        (sc_ui(), 4, true),
+        (sc_sl(), 8, false),
+        (sc_ul(), 8, true),
+        (uc_uc(), 4, false),
+        (uc_ss(), 4, false),
+        (uc_us(), 4, false),
+        (uc_si(), 4, false),
+        (uc_ui(), 4, true),
+        (uc_sl(), 8, false),
+        (uc_ul(), 8, true),
+        (ss_ss(), 4, false),
+        (ss_us(), 4, false),
+        (ss_si(), 4, false),
+        (ss_ui(), 4, true),
+        (ss_sl(), 8, false),
+        (ss_ul(), 8, true),
+        (us_us(), 4, false),
+        (us_si(), 4, false),
+        (us_ui(), 4, true),
+        (us_sl(), 8, false),
+        (us_ul(), 8, true),
+        (si_si(), 4, false),
+        (si_ui(), 4, true),
+        (si_sl(), 8, false),
+        (si_ul(), 8, true),
+        (ui_ui(), 4, true),
+        (ui_sl(), 8, false),
+        (ui_ul(), 8, true),
+        (sl_sl(), 8, false),
+        (sl_ul(), 8, true),
+        (ul_ul(), 8, true),
+    ].iter().enumerate() {
+        assert_eq!(common, size * 10 + unsigned as i32, "pair #{}", i);
+    }
+}
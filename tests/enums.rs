@@ -0,0 +1,18 @@
+use qas::prelude::*;
+
+qas!("tests/c/enums.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!((RED, GREEN, BLUE, LAST), (0, 5, 6, 13));
+    assert_eq!((ZERO, ONE), (0, 1));
+    assert_eq!(color_value(BLUE), 6);
+    assert_eq!(next_color(RED), GREEN);
+    assert_eq!(next_color(GREEN), BLUE);
+    assert_eq!(classify(0), 10);
+    assert_eq!(classify(6), 20);
+    assert_eq!(classify(13), 30);
+    assert_eq!(classify(7), -1);
+    assert_eq!(local_enum(), 3);
+}
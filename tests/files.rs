@@ -0,0 +1,21 @@
+mod first {
+    use qas::prelude::*;
+
+    qas!("tests/c/files.c");
+}
+
+mod second {
+    use qas::prelude::*;
+
+    qas!("tests/c/files_other.c");
+}
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(first::pa(), 3);
+    assert_eq!(first::typed(), 7);
+    assert_eq!(second::pa(), 60);
+    assert_eq!(first::green(), 2);
+    assert_eq!(second::light(), 6);
+}
@@ -0,0 +1,17 @@
+use qas::prelude::*;
+
+qas!("tests/c/floats.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(permissions(), 493);
+    assert_eq!(zero(), 0);
+    assert_eq!(exponents(), 1000.0 + 0.0025 + 10.0 + 5.0);
+    assert_eq!(leading_dot(), 3.0);
+    assert_eq!(hex(), 12.0 + 1.0 + 1.0);
+    assert_eq!(sizes(), 8000 + 800 + 40 + 4);
+    assert_eq!(single(), 2.5);
+    assert_eq!(truncated(), 29);
+    assert_eq!(big(), 1_000_000_000_000);
+}
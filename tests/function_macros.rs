@@ -0,0 +1,23 @@
+use qas::prelude::*;
+
+qas!("tests/c/function_macros.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(max(3, 7), 7);
+    assert_eq!(nested(2), 9);
+    assert_eq!(nested(5), 25);
+    assert_eq!(multiline(3), 18);
+    assert_eq!(rescanned(4), 12);
+    assert_eq!(indirect(3), 10);
+    assert_eq!(unsafe { recursive() }, 5);
+    assert_eq!(applied(2), 9);
+    assert_eq!(untouched(1), 2);
+    assert_eq!(unsafe { quoted() }, b'M');
+    assert_eq!(line(), 54);
+    assert_eq!(followed(), 6 + 8);
+    assert_eq!(unsafe { identity_count() }, 5);
+    assert_eq!(unsafe { painted() }, 7);
+    assert_eq!(opened(), !5 * 2);
+}
@@ -0,0 +1,24 @@
+use qas::prelude::*;
+
+qas!("tests/c/globals.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(unsafe { next() }, 1);
+    assert_eq!(unsafe { next() }, 2);
+    assert_eq!(unsafe { total() }, 6);
+    assert_eq!(unsafe { counter }, 2);
+    assert_eq!(capped(7), 3);
+    assert_eq!(capped(2), 2);
+    assert_eq!(prime(3), 7);
+    assert_eq!(unsafe { sum_table() }, 60);
+    assert_eq!(unsafe { through_pointer() }, 35);
+    assert_eq!(unsafe { moved() }, 13);
+    assert_eq!(unsafe { letter(1) }, b'a');
+    assert_eq!(unsafe { letter(5) }, 0);
+    assert_eq!(shadowed(4), 8);
+    assert_eq!(bump_shared(), 41);
+    assert_eq!(limit, 3);
+    assert_eq!(unsafe { *greeting.add(1) }, b'i');
+}
@@ -0,0 +1,22 @@
+use qas::prelude::*;
+
+qas!("tests/c/goto.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(cleanup(-1), -1);
+    assert_eq!(cleanup(0), 1);
+    assert_eq!(cleanup(1), 2);
+    assert_eq!(retry(5), 5);
+    assert_eq!(retry(0), 1);
+    assert_eq!(across(0), 1);
+    assert_eq!(across(3), 3);
+    assert_eq!(found(50), 1);
+    assert_eq!(found(20000), -1);
+    assert_eq!(both_ways(1), 1);
+    assert_eq!(both_ways(4), 5);
+    assert_eq!(shadowed(1), 1);
+    assert_eq!(nested_tail(3), 3);
+    nothing(1)
+}
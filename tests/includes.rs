@@ -0,0 +1,17 @@
+use qas::prelude::*;
+
+qas!("tests/c/includes.c", include = ["tests/c/include"]);
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(size(), 4);
+    assert_eq!(config_line(), 10);
+    assert_eq!(area(), 16);
+    assert_eq!(volume(), 64);
+    assert_eq!(is_even(10), 1);
+    assert_eq!(is_odd(7), 1);
+    assert_eq!(line(), 17);
+    assert!(unsafe { config_file().to_rust() }.ends_with("tests/c/include/config.h"));
+    assert_eq!(unsafe { renamed().to_rust() }, "renamed \"file\".c");
+}
@@ -0,0 +1,18 @@
+use qas::prelude::*;
+
+qas!("tests/c/literals.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(big(), 700);
+    assert_eq!(minus_one(), -1);
+    assert_eq!(all_ones(), u32::MAX);
+    assert_eq!(sizes(), 4000 + 800 + 40 + 4);
+    assert_eq!(suffixes(), 4 + 8 * 9);
+    assert_eq!(is_unsigned(), 1000 + 100 + 1);
+    assert_eq!(wide(), 8_000_000_000);
+    assert_eq!(truncated(), 44);
+    assert_eq!(negative_hex(), -16);
+    assert_eq!(smallest(), i64::MIN);
+}
@@ -0,0 +1,16 @@
+use qas::prelude::*;
+
+qas!("tests/c/locals.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(add_one(41), 42);
+    assert_eq!(first_of_three(1, 2), 1);
+    assert_eq!(shadowed(5), 5);
+    assert_eq!(incremented(5), 7);
+    assert_eq!(tripled(5), 15);
+    assert_eq!(widened(-5), -5);
+    assert_eq!(unsafe { greeting().to_rust() }, "Hello");
+    assert_eq!(uninit(), 0);
+}
@@ -0,0 +1,21 @@
+use qas::prelude::*;
+
+qas!("tests/c/loops.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(count_to(5), 5);
+    assert_eq!(count_to(-1), 0);
+    assert_eq!(count_inclusive(5), 5);
+    assert_eq!(count_even(5), 3);
+    assert_eq!(skip_with_step(6), 4);
+    assert_eq!(countdown(4), 4);
+    assert_eq!(at_least_once(0), 1);
+    assert_eq!(at_least_once(3), 3);
+    assert_eq!(do_continue(6), 3);
+    assert_eq!(forever(7), 7);
+    assert_eq!(nested(4), 6);
+    assert_eq!(below_unsigned(u32::MAX), 3);
+    assert_eq!(small_counter(), 10);
+}
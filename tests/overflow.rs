@@ -0,0 +1,41 @@
+use qas::prelude::*;
+
+qas!("tests/c/overflow.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(below_zero(), u32::MAX);
+    assert_eq!(product(1 << 31, 2), 0);
+    assert_eq!(shifted(3, 63), 1 << 63);
+    assert_eq!(steps(), 254);
+    assert_eq!(negated(1), u32::MAX);
+    assert_eq!(wrapped(i32::MAX, 0), i32::MIN);
+    assert_eq!(wrapped(i32::MAX, 1), i32::MAX);
+    assert_eq!(wrapped(2, 3), -6);
+    assert_eq!(checked(-5, 7), 2);
+    assert_eq!(checked_step(1), 4 + 5);
+    assert_eq!(trapped(7, 5), 2);
+    assert_eq!(after_include(1), 4);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "signed integer overflow at tests/c/overflow.c:37")]
+fn checked_overflow() {
+    checked(i32::MAX, 1);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "signed integer overflow at tests/c/overflow.c:54")]
+fn checked_overflow_after_include() {
+    after_include(i32::MAX);
+}
+
+#[cfg(test)]
+#[test]
+#[should_panic(expected = "signed integer overflow")]
+fn trapped_overflow() {
+    trapped(i32::MIN, 1);
+}
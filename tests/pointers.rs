@@ -0,0 +1,16 @@
+use qas::prelude::*;
+
+qas!("tests/c/pointers.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    let mut a = [1, 2, 3, 4];
+    let s = span { begin: a.as_mut_ptr(), end: a.as_mut_ptr().wrapping_add(4) };
+    assert_eq!(unsafe { length(s) }, 4);
+    assert_eq!(unsafe { total(a.as_ptr(), a.as_ptr().wrapping_add(3)) }, 6);
+    assert_eq!(unsafe { last(a.as_mut_ptr(), 4) }, 4);
+    assert_eq!(unsafe { walk() }, 423);
+    assert_eq!(nulls(core::ptr::null_mut()), -1);
+    assert_eq!(nulls(a.as_mut_ptr()), 1);
+}
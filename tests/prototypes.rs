@@ -0,0 +1,35 @@
+use qas::prelude::*;
+
+qas!("tests/c/prototypes.c");
+
+/// Functions, which are only declared in the C file
+mod native {
+    #[no_mangle]
+    pub extern "C" fn tripled(x: i32) -> i32 {
+        x * 3
+    }
+
+    #[no_mangle]
+    pub extern "C" fn doubled(x: i32) -> i32 {
+        x * 2
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(sum_of_squares(3, 4), 25);
+    assert_eq!(nothing(), 0);
+    assert_eq!(forward(5), 11);
+    assert_eq!(is_even(10), 1);
+    assert_eq!(is_odd(7), 1);
+    assert_eq!(is_even(7), 0);
+    assert_eq!(local(4), -4);
+    assert_eq!(guarded(4), 5);
+    let mut x = 7;
+    assert_eq!(unsafe { incremented(&mut x) }, 8);
+    assert_eq!(doubled(4), 8);
+    assert_eq!(unsafe { external(4) }, 12 + 8);
+    assert_eq!(early_product(), 12);
+    assert_eq!(decrement(5), 4);
+}
@@ -0,0 +1,26 @@
+use qas::prelude::*;
+
+qas!("tests/c/statics.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(unsafe { calls() }, 1);
+    assert_eq!(unsafe { calls() }, 2);
+    assert_eq!(unsafe { other_calls() }, 10);
+    assert_eq!(unsafe { calls() }, 3);
+    assert_eq!(unsafe { sequence() }, 5);
+    assert_eq!(unsafe { sequence() }, 6);
+    assert_eq!(unsafe { sequence() }, 7);
+    assert_eq!(unsafe { sequence() }, 5);
+    assert_eq!(unsafe { scoped(0) }, 101);
+    assert_eq!(unsafe { scoped(0) }, 102);
+    assert_eq!(unsafe { scoped(1) }, 1);
+    let p = unsafe { counter_address() };
+    assert_eq!(unsafe { *p }, 1);
+    assert_eq!(unsafe { counter_address() }, p);
+    assert_eq!(unsafe { *p }, 2);
+    assert_eq!(ticks(), 2);
+    assert_eq!(ticks(), 4);
+    assert_eq!(unsafe { other_calls_count() }, 8);
+}
@@ -0,0 +1,22 @@
+use qas::prelude::*;
+
+qas!("tests/c/stringify.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    unsafe {
+        assert_eq!(get_width(), 7);
+        assert_eq!(get_height(), 3);
+        let s = b"a + \"b\\n\"\0";
+        for (i, x) in s.iter().enumerate() {
+            assert_eq!(stringified(i as i32), *x);
+        }
+        assert_eq!([expanded(0), expanded(1), expanded(2)], *b"42\0");
+        assert_eq!([unexpanded(0), unexpanded(4), unexpanded(5)], *b"VE\0");
+        assert_eq!(empty(), 0);
+        assert_eq!(pasted(), 7 + 3 + 13 + 420);
+        assert_eq!(twice(), 23);
+        assert_eq!(number(), 15 + 31);
+    }
+}
@@ -0,0 +1,16 @@
+use qas::prelude::*;
+
+qas!("tests/c/structs.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(manhattan(make_point(2, 5)), 7);
+    assert_eq!(unsafe { linked(1, 2, 3) }, 9);
+    assert_eq!(copied(), 15);
+    assert_eq!(item(), 15);
+    assert_eq!(unsafe { nested() }, 12);
+    assert_eq!(size_item(), 24);
+    assert_eq!(size_outer(), 32);
+    assert_eq!(size_outer(), core::mem::size_of::<outer>() as u64);
+}
@@ -0,0 +1,30 @@
+use qas::prelude::*;
+
+qas!("tests/c/switch.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(plain(1), 1);
+    assert_eq!(plain(2), -1);
+    assert_eq!(plain(3), -1);
+    assert_eq!(plain(-1), -10);
+    assert_eq!(plain(5), 50);
+    assert_eq!(plain(42), 2);
+
+    assert_eq!(fallthrough(0), 3);
+    assert_eq!(fallthrough(1), 2);
+    assert_eq!(fallthrough(2), 1);
+    assert_eq!(fallthrough(3), -1);
+    assert_eq!(fallthrough(4), 0);
+
+    assert_eq!(default_in_middle(1), 4);
+    assert_eq!(default_in_middle(2), 1);
+    assert_eq!(default_in_middle(7), 3);
+
+    assert_eq!(inner_break(1, 1), 0);
+    assert_eq!(inner_break(1, 0), 1);
+    assert_eq!(inner_break(2, 0), -1);
+
+    assert_eq!(skip_in_loop(6), 6);
+}
@@ -0,0 +1,19 @@
+use qas::prelude::*;
+
+qas!("tests/c/unions.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(unsafe { float_bits(1.0) }, 0x3f80_0000);
+    assert_eq!(low_byte(0x1234), 0x35);
+    assert_eq!(as_double(make_int(3)), 3.0);
+    assert_eq!(as_double(make_float(2.5)), 2.5);
+    assert_eq!(size_wide(), 8);
+    assert_eq!(size_value(), 16);
+
+    let mut n: number = unsafe { core::mem::zeroed() };
+    unsafe { set_double(&mut n, 0.5) };
+    assert_eq!(number(&mut n), 0.5);
+    assert_eq!(unsafe { read_double(n) }, 0.5);
+}
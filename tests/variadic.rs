@@ -0,0 +1,18 @@
+use qas::prelude::*;
+
+qas!("tests/c/variadic.c");
+
+#[cfg(test)]
+#[test]
+fn main() {
+    assert_eq!(first(), 5);
+    assert_eq!(rest(), 6);
+    assert_eq!(call(), 6 + 7);
+    assert_eq!(opt(), 3 + 6);
+    assert_eq!(gnu(), 100 + 205);
+    assert_eq!(empty_opt(), 100 + 5);
+    let s = b"a, b, c\0";
+    for (i, x) in s.iter().enumerate() {
+        assert_eq!(unsafe { stringified(i as i32) }, *x);
+    }
+}